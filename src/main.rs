@@ -1,4 +1,6 @@
+use crate::pageviews::{BurstParams, PageViewEngine};
 use crate::wikigraph::GraphBuilder;
+use chrono::NaiveDate;
 use clap::{Arg, ArgMatches, Command};
 use std::error::Error;
 
@@ -94,10 +96,179 @@ fn main() -> Result<(), Box<dyn Error>> {
                         .help("The Wiki ID of the article"),
                 ),
         )
+        .subcommand(
+            Command::new("top-articles")
+                .about("Retrieve the top N articles by views over a date range")
+                .arg(
+                    Arg::new("start-date")
+                        .long("start-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("Start date (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("end-date")
+                        .long("end-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("End date (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("n")
+                        .long("n")
+                        .short('n')
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of top articles to return"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify-bloom-filter")
+                .about(
+                    "Write matching bloom-filtered and non-bloom-filtered pageview fixtures \
+                     and assert they're read back identically",
+                )
+                .arg(
+                    Arg::new("verify-dir")
+                        .long("verify-dir")
+                        .default_value("bloom_filter_verify")
+                        .help("Scratch directory the fixtures are written under"),
+                )
+                .arg(
+                    Arg::new("date")
+                        .long("date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("Date to stamp the fixture pageview files with"),
+                ),
+        )
+        .subcommand(
+            Command::new("save-snapshot")
+                .about("Build the graph from Parquet and write it to a memory-mappable snapshot file")
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .required(true)
+                        .help("Path to write the snapshot to"),
+                ),
+        )
+        .subcommand(
+            Command::new("load-snapshot")
+                .about("Load a graph from a snapshot written by save-snapshot, bypassing Parquet entirely")
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .short('p')
+                        .required(true)
+                        .help("Path to the snapshot file"),
+                ),
+        )
+        .subcommand(
+            Command::new("search-categories")
+                .about("Typo-tolerant fuzzy search over category names")
+                .arg(
+                    Arg::new("query")
+                        .long("query")
+                        .short('q')
+                        .required(true)
+                        .help("Free-text category name query"),
+                )
+                .arg(
+                    Arg::new("max-edits")
+                        .long("max-edits")
+                        .default_value("2")
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Maximum Levenshtein edit distance to tolerate"),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Maximum number of results to return"),
+                ),
+        )
+        .subcommand(
+            Command::new("category-trend-fast")
+                .about("Print a category's daily view totals using the transposed fast-path aggregation")
+                .arg(
+                    Arg::new("category-id")
+                        .long("category-id")
+                        .short('c')
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The Wiki ID of the category"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .short('n')
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Depth for recursive article lookup"),
+                )
+                .arg(
+                    Arg::new("start-date")
+                        .long("start-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("Start date (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("end-date")
+                        .long("end-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("End date (YYYY-MM-DD)"),
+                ),
+        )
+        .subcommand(
+            Command::new("detect-bursts")
+                .about("Detect burst/spike days in a category's pageview trend")
+                .arg(
+                    Arg::new("category-id")
+                        .long("category-id")
+                        .short('c')
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The Wiki ID of the category"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .short('n')
+                        .default_value("1")
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Depth for recursive article lookup"),
+                )
+                .arg(
+                    Arg::new("start-date")
+                        .long("start-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("Start date (YYYY-MM-DD)"),
+                )
+                .arg(
+                    Arg::new("end-date")
+                        .long("end-date")
+                        .required(true)
+                        .value_parser(clap::value_parser!(NaiveDate))
+                        .help("End date (YYYY-MM-DD)"),
+                ),
+        )
         .get_matches();
 
-    // Load the graph
     let data_dir = matches.get_one::<String>("data-dir").unwrap();
+
+    // `load-snapshot` reads a prebuilt snapshot instead of rebuilding the
+    // graph from Parquet, so it's dispatched before the common build step
+    // every other subcommand shares.
+    if let Some(("load-snapshot", sub_m)) = matches.subcommand() {
+        return handle_load_snapshot(sub_m);
+    }
+
+    // Load the graph
     let graph = GraphBuilder::build(data_dir)?;
 
     // Dispatch subcommands
@@ -109,12 +280,141 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
         Some(("list-parent-categories", sub_m)) => handle_get_parent_categories(&graph, sub_m),
         Some(("list-article-categories", sub_m)) => handle_get_article_categories(&graph, sub_m),
+        Some(("top-articles", sub_m)) => handle_top_articles(data_dir, sub_m)?,
+        Some(("verify-bloom-filter", sub_m)) => handle_verify_bloom_filter(sub_m)?,
+        Some(("save-snapshot", sub_m)) => handle_save_snapshot(&graph, sub_m)?,
+        Some(("search-categories", sub_m)) => handle_search_categories(&graph, sub_m),
+        Some(("category-trend-fast", sub_m)) => handle_category_trend_fast(&graph, sub_m)?,
+        Some(("detect-bursts", sub_m)) => handle_detect_bursts(&graph, sub_m)?,
         _ => println!("No valid subcommand provided. Use --help for usage."),
     }
 
     Ok(())
 }
 
+fn handle_save_snapshot(graph: &wikigraph::WikiGraph, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let output = matches.get_one::<String>("output").unwrap();
+    graph.save_snapshot(output)?;
+    println!("Wrote snapshot to {}.", output);
+    Ok(())
+}
+
+fn handle_load_snapshot(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let path = matches.get_one::<String>("path").unwrap();
+    let graph = wikigraph::WikiGraph::open_mmapped(path)
+        .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
+    println!(
+        "Loaded snapshot from {}: {} categories, {} articles.",
+        path,
+        graph.cat_dense_to_original.len(),
+        graph.art_dense_to_original.len()
+    );
+    Ok(())
+}
+
+fn handle_search_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+    let query = matches.get_one::<String>("query").unwrap();
+    let max_edits = *matches.get_one::<u8>("max-edits").unwrap();
+    let limit = *matches.get_one::<usize>("limit").unwrap();
+
+    let results = graph.search_categories(query, max_edits, limit);
+    println!("Found {} categories matching '{}':", results.len(), query);
+    for (id, name, distance) in results {
+        println!(" - {}: {} (edit distance {})", id, name, distance);
+    }
+}
+
+fn handle_top_articles(data_dir: &str, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let start_date = *matches.get_one::<NaiveDate>("start-date").unwrap();
+    let end_date = *matches.get_one::<NaiveDate>("end-date").unwrap();
+    let n = *matches.get_one::<usize>("n").unwrap();
+
+    let top_articles = PageViewEngine::get_top_articles(data_dir, start_date, end_date, n)?;
+    println!(
+        "Top {} articles by views from {} to {}:",
+        top_articles.len(),
+        start_date,
+        end_date
+    );
+    for (page_id, views) in top_articles {
+        println!(" - {}: {} views", page_id, views);
+    }
+
+    Ok(())
+}
+
+fn handle_verify_bloom_filter(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let verify_dir = matches.get_one::<String>("verify-dir").unwrap();
+    let date = *matches.get_one::<NaiveDate>("date").unwrap();
+
+    pageviews::verify_bloom_filter_consistency(verify_dir, date)?;
+    println!("Bloom filter and non-bloom-filter reads agree for {}.", date);
+
+    Ok(())
+}
+
+/// Every date from `start` to `end`, inclusive, the date range
+/// `PageViewEngine::load_history` expects.
+fn dates_between(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    let mut curr = start;
+    while curr <= end {
+        dates.push(curr);
+        curr = curr.succ_opt().unwrap();
+    }
+    dates
+}
+
+fn handle_category_trend_fast(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+) -> Result<(), Box<dyn Error>> {
+    let category_id = *matches.get_one::<u32>("category-id").unwrap();
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    let start_date = *matches.get_one::<NaiveDate>("start-date").unwrap();
+    let end_date = *matches.get_one::<NaiveDate>("end-date").unwrap();
+
+    let article_mask = graph.get_articles_in_category(category_id, depth);
+    let engine = PageViewEngine::load_history(graph, dates_between(start_date, end_date))?;
+    let trend = engine.get_category_trend_fast(&article_mask, start_date, end_date);
+
+    println!("Daily views for category {} ({} to {}):", category_id, start_date, end_date);
+    for (date, views) in trend {
+        println!(" - {}: {} views", date, views);
+    }
+
+    Ok(())
+}
+
+fn handle_detect_bursts(graph: &wikigraph::WikiGraph, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let category_id = *matches.get_one::<u32>("category-id").unwrap();
+    let depth = *matches.get_one::<u8>("depth").unwrap();
+    let start_date = *matches.get_one::<NaiveDate>("start-date").unwrap();
+    let end_date = *matches.get_one::<NaiveDate>("end-date").unwrap();
+
+    let article_mask = graph.get_articles_in_category(category_id, depth);
+    let params = BurstParams::default();
+    let history_start = start_date - chrono::Duration::days(params.window as i64);
+    let engine = PageViewEngine::load_history(graph, dates_between(history_start, end_date))?;
+    let bursts = engine.detect_bursts(&article_mask, start_date, end_date, params);
+
+    println!(
+        "Found {} burst event(s) for category {} ({} to {}):",
+        bursts.len(),
+        category_id,
+        start_date,
+        end_date
+    );
+    for burst in bursts {
+        println!(
+            " - {} to {} (peak {}, intensity {:.2})",
+            burst.start_date, burst.end_date, burst.peak_date, burst.intensity
+        );
+    }
+
+    Ok(())
+}
+
 fn handle_get_articles(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
     let category_id: &u32 = matches.get_one::<u32>("category-id").unwrap();
     let depth: &u8 = matches.get_one::<u8>("depth").unwrap();
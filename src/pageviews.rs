@@ -1,11 +1,25 @@
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate};
 use dotenv::dotenv;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::statistics::Statistics;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::{RecordWriter, RowAccessor};
+use parquet::schema::types::ColumnPath;
+use parquet_derive::ParquetRecordWriter;
 use polars::{
     frame::DataFrame,
     prelude::{LazyFrame, PlPath},
 };
 use roaring::RoaringBitmap;
-use std::{collections::HashMap, error::Error, fs::File, path::Path, sync::Arc};
+use std::{
+    collections::{BinaryHeap, HashMap},
+    error::Error,
+    fs::File,
+    path::Path,
+    sync::Arc,
+};
 
 use crate::wikigraph::WikiGraph;
 
@@ -13,6 +27,48 @@ pub struct PageViewEngine {
     // Map Date -> Vector of pageviews (Index is Dense Article ID)
     // We use Arc to make it cheap to clone/share across web threads
     daily_views: HashMap<NaiveDate, Vec<u32>>,
+
+    // Transposed, contiguous mirror of `daily_views` for
+    // `get_category_trend_fast`: row `r` (`views_matrix[r * num_articles
+    // .. (r + 1) * num_articles]`) holds the day at `matrix_date_index`'s
+    // key mapping to `r`, so a single article's history across many days
+    // is a fixed-stride walk instead of one HashMap hit per day.
+    views_matrix: Vec<u32>,
+    matrix_date_index: HashMap<NaiveDate, usize>,
+    num_articles: usize,
+}
+
+/// Tuning knobs for [`PageViewEngine::detect_bursts`].
+#[derive(Debug, Clone, Copy)]
+pub struct BurstParams {
+    /// Length `w` of the trailing baseline window, in days.
+    pub window: usize,
+    /// Minimum z-score `k` for a day to be flagged as in-burst.
+    pub z_threshold: f64,
+    /// Minimum absolute delta above the window mean required to flag a
+    /// day, guarding against flagging noise in a near-flat (σ ≈ 0) region.
+    pub min_delta: f64,
+}
+
+impl Default for BurstParams {
+    fn default() -> Self {
+        Self {
+            window: 14,
+            z_threshold: 3.0,
+            min_delta: 1.0,
+        }
+    }
+}
+
+/// A run of one or more contiguous in-burst days.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurstEvent {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// The day within `[start_date, end_date]` with the highest z-score.
+    pub peak_date: NaiveDate,
+    /// The z-score at `peak_date`.
+    pub intensity: f64,
 }
 
 pub fn load_bin_file(path: &str, expected_size: usize) -> Result<Vec<u32>> {
@@ -71,11 +127,84 @@ impl PageViewEngine {
         results
     }
 
+    /// Flags burst/spike runs in a category's trend using a streaming
+    /// z-score / moving-baseline detector: for each day, the trailing
+    /// `params.window` days are the baseline, and the day is in-burst when
+    /// it's at least `params.z_threshold` standard deviations above that
+    /// baseline's mean (and the delta clears `params.min_delta`, to avoid
+    /// flagging noise when the baseline is flat). Contiguous in-burst days
+    /// merge into one `BurstEvent`, peaking at the day with the highest
+    /// z-score. Days before `start_date` are only used to seed the first
+    /// `window` days' baseline, never flagged themselves.
+    pub fn detect_bursts(
+        &self,
+        article_mask: &RoaringBitmap,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        params: BurstParams,
+    ) -> Vec<BurstEvent> {
+        let window = params.window.max(1);
+        let history_start = start_date - Duration::days(window as i64);
+        let series = self.get_category_trend(article_mask, history_start, end_date);
+
+        let dates: Vec<NaiveDate> = series.iter().map(|&(d, _)| d).collect();
+        let values: Vec<f64> = series.iter().map(|&(_, v)| v as f64).collect();
+
+        let mut events = Vec::new();
+        let mut current_run: Option<(usize, usize, f64)> = None; // (start_idx, peak_idx, peak_z)
+
+        for i in 0..values.len() {
+            if dates[i] < start_date || i < window {
+                continue; // still inside the baseline-only lead-in
+            }
+
+            let baseline = &values[i - window..i];
+            let mean = baseline.iter().sum::<f64>() / window as f64;
+            let variance =
+                baseline.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window as f64;
+            let std_dev = variance.sqrt();
+            let delta = values[i] - mean;
+            let z = if std_dev > 0.0 { delta / std_dev } else { 0.0 };
+            let is_burst = std_dev > 0.0 && delta >= params.min_delta && z >= params.z_threshold;
+
+            if is_burst {
+                match &mut current_run {
+                    Some((_, peak_idx, peak_z)) if z > *peak_z => {
+                        *peak_idx = i;
+                        *peak_z = z;
+                    }
+                    Some(_) => {}
+                    None => current_run = Some((i, i, z)),
+                }
+            } else if let Some((start_idx, peak_idx, peak_z)) = current_run.take() {
+                events.push(BurstEvent {
+                    start_date: dates[start_idx],
+                    end_date: dates[i - 1],
+                    peak_date: dates[peak_idx],
+                    intensity: peak_z,
+                });
+            }
+        }
+
+        if let Some((start_idx, peak_idx, peak_z)) = current_run {
+            events.push(BurstEvent {
+                start_date: dates[start_idx],
+                end_date: dates[values.len() - 1],
+                peak_date: dates[peak_idx],
+                intensity: peak_z,
+            });
+        }
+
+        events
+    }
+
     pub fn load_history(
         graph: &WikiGraph,
         dates: Vec<NaiveDate>,
     ) -> Result<PageViewEngine, Box<dyn Error>> {
         let mut daily_views = HashMap::new();
+        let mut views_matrix = Vec::new();
+        let mut matrix_date_index = HashMap::new();
         let num_articles = graph.art_dense_to_original.len();
         dotenv().ok();
 
@@ -107,10 +236,412 @@ impl PageViewEngine {
                 }
             }
 
+            // The transposed matrix only ever grows by whole rows, so this
+            // day's row index is just the row count so far.
+            matrix_date_index.insert(date, views_matrix.len() / num_articles.max(1));
+            views_matrix.extend_from_slice(&day_vec);
+
             daily_views.insert(date, day_vec);
             println!("Loaded views for {}", date);
         }
 
-        Ok(PageViewEngine { daily_views })
+        Ok(PageViewEngine {
+            daily_views,
+            views_matrix,
+            matrix_date_index,
+            num_articles,
+        })
+    }
+
+    /// Like [`Self::get_category_trend`], but reads from the transposed
+    /// `views_matrix` instead of re-scanning `article_mask` once per day
+    /// against per-day `HashMap`-backed vectors. The date range is
+    /// resolved to row indices once up front (one `HashMap` lookup per
+    /// day), then `article_mask` is iterated once in total, every article
+    /// in it walking its column across that same resolved slice.
+    pub fn get_category_trend_fast(
+        &self,
+        article_mask: &RoaringBitmap,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Vec<(NaiveDate, u64)> {
+        let num_days = (end_date - start_date).num_days() as usize + 1;
+        let mut dates = Vec::with_capacity(num_days);
+        let mut rows: Vec<Option<usize>> = Vec::with_capacity(num_days);
+
+        let mut curr = start_date;
+        for _ in 0..num_days {
+            rows.push(self.matrix_date_index.get(&curr).copied());
+            dates.push(curr);
+            curr = curr.succ_opt().unwrap();
+        }
+
+        let mut totals = vec![0u64; num_days];
+
+        // RoaringBitmap iteration order is sorted, which is cache-friendly
+        // for the strided column walk below.
+        for article_dense_id in article_mask.iter() {
+            let article_idx = article_dense_id as usize;
+            if article_idx >= self.num_articles {
+                continue;
+            }
+            for (day_idx, row) in rows.iter().enumerate() {
+                if let Some(row) = row {
+                    let offset = row * self.num_articles + article_idx;
+                    totals[day_idx] += self.views_matrix[offset] as u64;
+                }
+            }
+        }
+
+        dates.into_iter().zip(totals).collect()
+    }
+
+    /// Reads pageviews for just `qids` on `date`, instead of the whole day's
+    /// file. Each row group is skipped entirely, without being read, when
+    /// it can be proven to hold none of `qids`:
+    ///   - by its `page_id` split-block bloom filter, when the writer built
+    ///     one (see `convert_pageviews_to_parquet`'s bloom filter option) -
+    ///     this catches scattered qid sets that a contiguous range wouldn't;
+    ///   - otherwise by its `[min, max]` page_id range statistic, which is
+    ///     only tight when the file was written sorted by `page_id`.
+    /// Row groups with neither are read and filtered in full, so unsorted
+    /// or bloom-less files still return correct, if unpruned, results.
+    pub fn read_pageviews_for_qids(
+        data_dir: &str,
+        date: NaiveDate,
+        qids: &[u32],
+    ) -> Result<HashMap<u32, u32>, Box<dyn Error>> {
+        let filename = format!("{}/views_{}.parquet", data_dir, date);
+        if !std::path::Path::new(&filename).exists() {
+            return Ok(HashMap::new());
+        }
+
+        let file = File::open(&filename)?;
+        let reader = SerializedFileReader::new(file)?;
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let qid_col = schema
+            .columns()
+            .iter()
+            .position(|c| c.name() == "page_id")
+            .ok_or("page_id column missing from schema")?;
+        let views_col = schema
+            .columns()
+            .iter()
+            .position(|c| c.name() == "views")
+            .ok_or("views column missing from schema")?;
+
+        let mut result = HashMap::new();
+
+        for rg_idx in 0..reader.metadata().num_row_groups() {
+            let row_group_reader = reader.get_row_group(rg_idx)?;
+
+            if let Some(bloom) = row_group_reader.get_column_bloom_filter(qid_col) {
+                let maybe_present = qids.iter().any(|&qid| bloom.check(&(qid as i32)));
+                if !maybe_present {
+                    continue; // none of `qids` can be in this row group
+                }
+            } else if let Some((min_qid, max_qid)) = row_group_i32_range(&reader, rg_idx, qid_col)
+            {
+                let overlaps = qids
+                    .iter()
+                    .any(|&qid| (qid as i32) >= min_qid && (qid as i32) <= max_qid);
+                if !overlaps {
+                    continue; // `qids` all fall outside this row group's range
+                }
+            }
+
+            for row in row_group_reader.get_row_iter(None)? {
+                let row = row?;
+                let qid = row.get_uint(qid_col)? as u32;
+                if qids.contains(&qid) {
+                    let views = row.get_uint(views_col)? as u32;
+                    result.insert(qid, views);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the `n` most-viewed articles across `[start_date, end_date]`
+    /// without fully scanning and sorting every row. When a day's file was
+    /// written with row groups sorted by `views` descending, each row
+    /// group's `views` max statistic bounds how much any row still unread
+    /// *in that file* could contribute; summed across every day still
+    /// outstanding, that's an upper bound on how much a fresh or
+    /// already-seen qid could still gain. Once a size-`n` heap is full and
+    /// its minimum total is at least that bound, no unread row can change
+    /// the top-n, so scanning stops. Files without `views` statistics are
+    /// read in full (same cost as the old scan + sort). Ties at the n-th
+    /// boundary are broken by ascending qid.
+    pub fn get_top_articles(
+        data_dir: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        n: usize,
+    ) -> Result<Vec<(u32, u64)>, Box<dyn Error>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        struct DayFile {
+            reader: SerializedFileReader<File>,
+            qid_col: usize,
+            views_col: usize,
+            // Row group indices for this file, ordered by descending
+            // `views` max statistic (re-derived defensively rather than
+            // assumed, in case the file predates the sorted writer).
+            row_group_order: Vec<usize>,
+            next: usize,
+        }
+
+        let mut days: Vec<DayFile> = Vec::new();
+        let mut curr = start_date;
+        while curr <= end_date {
+            let filename = format!("{}/views_{}.parquet", data_dir, curr);
+            if std::path::Path::new(&filename).exists() {
+                let file = File::open(&filename)?;
+                let reader = SerializedFileReader::new(file)?;
+                let schema = reader.metadata().file_metadata().schema_descr();
+                let qid_col = schema
+                    .columns()
+                    .iter()
+                    .position(|c| c.name() == "page_id")
+                    .ok_or("page_id column missing from schema")?;
+                let views_col = schema
+                    .columns()
+                    .iter()
+                    .position(|c| c.name() == "views")
+                    .ok_or("views column missing from schema")?;
+
+                let mut row_group_order: Vec<usize> =
+                    (0..reader.metadata().num_row_groups()).collect();
+                row_group_order.sort_by_key(|&i| {
+                    std::cmp::Reverse(row_group_max_views(&reader, i, views_col))
+                });
+
+                days.push(DayFile {
+                    reader,
+                    qid_col,
+                    views_col,
+                    row_group_order,
+                    next: 0,
+                });
+            }
+            curr = curr.succ_opt().unwrap();
+        }
+
+        let mut partial_sums: HashMap<u32, u64> = HashMap::new();
+        let mut heap: BinaryHeap<ArticleTotal> = BinaryHeap::new();
+
+        loop {
+            // Next-row-group max per still-open day, and their sum: the
+            // most any qid could still gain from unread data.
+            let mut remaining_bound: u64 = 0;
+            let mut best_day: Option<usize> = None;
+            let mut best_day_bound: u64 = 0;
+
+            for (i, day) in days.iter().enumerate() {
+                if day.next < day.row_group_order.len() {
+                    let rg_idx = day.row_group_order[day.next];
+                    let bound = row_group_max_views(&day.reader, rg_idx, day.views_col);
+                    remaining_bound = remaining_bound.saturating_add(bound);
+                    if best_day.is_none() || bound > best_day_bound {
+                        best_day = Some(i);
+                        best_day_bound = bound;
+                    }
+                }
+            }
+
+            let Some(day_idx) = best_day else {
+                break; // every day's row groups have been read
+            };
+
+            if heap.len() >= n {
+                if let Some(worst) = heap.peek() {
+                    if worst.total_views >= remaining_bound {
+                        break; // no unread row could unseat the current top-n
+                    }
+                }
+            }
+
+            // Read whichever day has the largest potential remaining
+            // contribution next, so the bound tightens as fast as possible.
+            let day = &mut days[day_idx];
+            let rg_idx = day.row_group_order[day.next];
+            day.next += 1;
+
+            let row_group_reader = day.reader.get_row_group(rg_idx)?;
+            let mut updated_qids = Vec::new();
+            for row in row_group_reader.get_row_iter(None)? {
+                let row = row?;
+                let qid = row.get_uint(day.qid_col)? as u32;
+                let views = row.get_uint(day.views_col)? as u64;
+                *partial_sums.entry(qid).or_insert(0) += views;
+                updated_qids.push(qid);
+            }
+
+            // Refresh the heap only for qids this row group actually
+            // touched; everything else is unchanged this round.
+            for qid in updated_qids {
+                let entry = ArticleTotal {
+                    total_views: partial_sums[&qid],
+                    qid,
+                };
+                if heap.iter().any(|e| e.qid == qid) {
+                    heap.retain(|e| e.qid != qid);
+                    heap.push(entry);
+                } else if heap.len() < n {
+                    heap.push(entry);
+                } else if let Some(worst) = heap.peek() {
+                    if entry.total_views > worst.total_views
+                        || (entry.total_views == worst.total_views && entry.qid < worst.qid)
+                    {
+                        heap.pop();
+                        heap.push(entry);
+                    }
+                }
+            }
+        }
+
+        let mut result: Vec<(u32, u64)> = heap
+            .into_iter()
+            .map(|entry| (entry.qid, entry.total_views))
+            .collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        result.truncate(n);
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ArticleTotal {
+    total_views: u64,
+    qid: u32,
+}
+
+impl Ord for ArticleTotal {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed so `BinaryHeap::peek`/`pop` surface the *worst* kept
+        // entry (lowest views, then highest qid) - the one to evict when a
+        // better candidate shows up and the heap is already at size n.
+        other
+            .total_views
+            .cmp(&self.total_views)
+            .then_with(|| self.qid.cmp(&other.qid))
+    }
+}
+
+impl PartialOrd for ArticleTotal {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
+
+/// The `page_id` column's `[min, max]` statistic for one row group, or
+/// `None` if the row group carries no statistics (or a non-integer type),
+/// in which case the caller must fall back to reading the row group in
+/// full rather than assuming it can be skipped.
+fn row_group_i32_range(
+    reader: &SerializedFileReader<File>,
+    row_group_idx: usize,
+    col_idx: usize,
+) -> Option<(i32, i32)> {
+    reader
+        .metadata()
+        .row_group(row_group_idx)
+        .column(col_idx)
+        .statistics()
+        .and_then(|stats| match stats {
+            Statistics::Int32(s) => s.min_opt().zip(s.max_opt()).map(|(&lo, &hi)| (lo, hi)),
+            Statistics::Int64(s) => s
+                .min_opt()
+                .zip(s.max_opt())
+                .map(|(&lo, &hi)| (lo as i32, hi as i32)),
+            _ => None,
+        })
+}
+
+#[derive(Debug, ParquetRecordWriter)]
+struct PageViewFixtureRow {
+    page_id: u32,
+    views: u32,
+}
+
+/// Writes the same rows to `{dir}/views_{date}.parquet` twice - once with a
+/// split-block bloom filter on `page_id`, once without - and asserts that
+/// `read_pageviews_for_qids` returns identical results from both files, so
+/// its bloom-filter and range-statistic code paths stay provably in
+/// agreement. Both fixtures are written with the default statistics, so
+/// the full-scan fallback is not exercised. Manual verification routine, analogous to
+/// `topictrend_cli::simulation::verify`.
+pub fn verify_bloom_filter_consistency(dir: &str, date: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let rows = vec![
+        PageViewFixtureRow {
+            page_id: 1,
+            views: 100,
+        },
+        PageViewFixtureRow {
+            page_id: 2,
+            views: 200,
+        },
+        PageViewFixtureRow {
+            page_id: 3,
+            views: 300,
+        },
+    ];
+    let qids = [1_u32, 3_u32, 999_u32]; // 999 is absent from both fixtures
+
+    let write_fixture = |path: &str, bloom_filter: bool| -> Result<(), Box<dyn Error>> {
+        let schema = rows.as_slice().schema()?;
+        let mut builder = WriterProperties::builder().set_compression(Compression::SNAPPY);
+        if bloom_filter {
+            builder = builder
+                .set_column_bloom_filter_enabled(ColumnPath::from("page_id"), true);
+        }
+        let file = File::create(path)?;
+        let mut writer = SerializedFileWriter::new(file, schema, Arc::new(builder.build()))?;
+        let mut row_group = writer.next_row_group()?;
+        rows.as_slice().write_to_row_group(&mut row_group)?;
+        row_group.close()?;
+        writer.close()?;
+        Ok(())
+    };
+
+    let bloom_dir = format!("{}/bloom", dir);
+    let no_bloom_dir = format!("{}/no_bloom", dir);
+    std::fs::create_dir_all(&bloom_dir)?;
+    std::fs::create_dir_all(&no_bloom_dir)?;
+    write_fixture(&format!("{}/views_{}.parquet", bloom_dir, date), true)?;
+    write_fixture(&format!("{}/views_{}.parquet", no_bloom_dir, date), false)?;
+
+    let with_bloom = PageViewEngine::read_pageviews_for_qids(&bloom_dir, date, &qids)?;
+    let without_bloom = PageViewEngine::read_pageviews_for_qids(&no_bloom_dir, date, &qids)?;
+    assert_eq!(with_bloom, without_bloom);
+    assert_eq!(with_bloom.get(&1), Some(&100));
+    assert_eq!(with_bloom.get(&3), Some(&300));
+    assert_eq!(with_bloom.get(&999), None);
+
+    Ok(())
+}
+
+/// The `views` column's max statistic for one row group, or `u64::MAX` if
+/// the row group carries no statistics - forcing it to always be read
+/// rather than silently skipped.
+fn row_group_max_views(
+    reader: &SerializedFileReader<File>,
+    row_group_idx: usize,
+    views_col: usize,
+) -> u64 {
+    reader
+        .metadata()
+        .row_group(row_group_idx)
+        .column(views_col)
+        .statistics()
+        .and_then(|stats| match stats {
+            Statistics::Int32(s) => s.max_opt().map(|&v| v as u64),
+            Statistics::Int64(s) => s.max_opt().map(|&v| v as u64),
+            _ => None,
+        })
+        .unwrap_or(u64::MAX)
+}
@@ -8,6 +8,11 @@ use parquet_derive::ParquetRecordWriter;
 use std::fs::File;
 use std::sync::Arc;
 
+/// Rows buffered per Parquet row group before being flushed and cleared, so
+/// peak memory stays bounded by this batch size rather than the size of the
+/// `categorylinks` table (tens of millions of rows for enwiki).
+const BATCH_SIZE: usize = 1_000_000;
+
 #[derive(Debug, ParquetRecordWriter)]
 struct GraphRelation {
     parent: u32,
@@ -19,6 +24,20 @@ struct CategoryRelation {
     parent_category: i32,
 }
 
+fn flush_batch(
+    writer: &mut SerializedFileWriter<File>,
+    batch: &mut Vec<GraphRelation>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let mut row_group = writer.next_row_group()?;
+    batch.as_slice().write_to_row_group(&mut row_group)?;
+    row_group.close()?;
+    batch.clear();
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
@@ -43,94 +62,62 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         JOIN page ON page_namespace = 14 AND page_title = cl_to
         WHERE cl_type = 'subcat'
      ";
-    let results: Vec<CategoryRelation> =
-        conn.query_map(query, |(category, parent_category)| CategoryRelation {
-            category,
-            parent_category,
-        })?;
-
-    println!("Retrieved {} records", results.len());
-
-    // Forward graph: Category ID -> List of Child Category IDs
-    let mut cat_children: Vec<Vec<u32>> = Vec::new();
 
-    // Reverse graph: Category ID -> List of Parent Category IDs
-    let mut cat_parents: Vec<Vec<u32>> = Vec::new();
+    // A schema sample - `ParquetRecordWriter` derives the schema from the
+    // struct definition, not the data, so an empty slice is enough to open
+    // both writers up front.
+    let schema_sample: Vec<GraphRelation> = Vec::new();
+    let schema = schema_sample.as_slice().schema()?;
 
-    for record in &results {
-        let category = record.category as usize;
-        let parent_category = record.parent_category as usize;
-
-        // Ensure the vectors are large enough to hold the indices
-        if category >= cat_parents.len() {
-            cat_parents.resize(category + 1, Vec::new());
-        }
-        if parent_category >= cat_children.len() {
-            cat_children.resize(parent_category + 1, Vec::new());
-        }
-
-        // Populate the forward graph
-        cat_children[parent_category].push(category as u32);
-
-        // Populate the reverse graph
-        cat_parents[category].push(parent_category as u32);
-    }
-
-    println!("Forward graph (cat_children) and reverse graph (cat_parents) prepared.");
+    let forward_file = File::create("data/cat_children.parquet")?;
+    let forward_props = Arc::new(WriterProperties::builder().build());
+    let mut forward_writer = SerializedFileWriter::new(forward_file, schema.clone(), forward_props)?;
 
-    // Flatten cat_children into a list of GraphRelation records
-    let mut forward_relations = Vec::new();
-    for (parent, children) in cat_children.iter().enumerate() {
-        for &child in children {
-            forward_relations.push(GraphRelation {
-                parent: parent as u32,
-                child,
-            });
+    let reverse_file = File::create("data/cat_parents.parquet")?;
+    let reverse_props = Arc::new(WriterProperties::builder().build());
+    let mut reverse_writer = SerializedFileWriter::new(reverse_file, schema, reverse_props)?;
+
+    let mut forward_batch: Vec<GraphRelation> = Vec::with_capacity(BATCH_SIZE);
+    let mut reverse_batch: Vec<GraphRelation> = Vec::with_capacity(BATCH_SIZE);
+    let mut total: usize = 0;
+
+    // Stream the result set row by row instead of collecting it into a
+    // `Vec<CategoryRelation>` first, so peak memory is bounded by
+    // `BATCH_SIZE` rather than the table size. The forward and reverse
+    // graphs are the same (parent, child) edges, just written to separate
+    // files, so each streamed row is appended to both batches directly
+    // rather than built up via an intermediate adjacency list.
+    let rows = conn.query_iter(query)?;
+    for row in rows {
+        let row = row?;
+        let (category, parent_category): (i32, i32) = from_row(row);
+
+        forward_batch.push(GraphRelation {
+            parent: parent_category as u32,
+            child: category as u32,
+        });
+        reverse_batch.push(GraphRelation {
+            parent: parent_category as u32,
+            child: category as u32,
+        });
+        total += 1;
+
+        if forward_batch.len() >= BATCH_SIZE {
+            flush_batch(&mut forward_writer, &mut forward_batch)?;
+            flush_batch(&mut reverse_writer, &mut reverse_batch)?;
         }
     }
 
-    // Flatten cat_parents into a list of GraphRelation records
-    let mut reverse_relations = Vec::new();
-    for (child, parents) in cat_parents.iter().enumerate() {
-        for &parent in parents {
-            reverse_relations.push(GraphRelation {
-                parent,
-                child: child as u32,
-            });
-        }
-    }
+    flush_batch(&mut forward_writer, &mut forward_batch)?;
+    flush_batch(&mut reverse_writer, &mut reverse_batch)?;
 
-    // Write forward_relations to a Parquet file
-    let forward_file = File::create("data/cat_children.parquet")?;
-    let forward_props = Arc::new(WriterProperties::builder().build());
-    let mut forward_writer = SerializedFileWriter::new(
-        forward_file,
-        forward_relations.as_slice().schema()?,
-        forward_props,
-    )?;
-    let mut forward_row_group = forward_writer.next_row_group()?;
-    forward_relations
-        .as_slice()
-        .write_to_row_group(&mut forward_row_group)?;
-    forward_row_group.close()?;
     forward_writer.close()?;
-
-    // Write reverse_relations to a Parquet file
-    let reverse_file = File::create("data/cat_parents.parquet")?;
-    let reverse_props = Arc::new(WriterProperties::builder().build());
-    let mut reverse_writer = SerializedFileWriter::new(
-        reverse_file,
-        reverse_relations.as_slice().schema()?,
-        reverse_props,
-    )?;
-    let mut reverse_row_group = reverse_writer.next_row_group()?;
-    reverse_relations
-        .as_slice()
-        .write_to_row_group(&mut reverse_row_group)?;
-    reverse_row_group.close()?;
     reverse_writer.close()?;
 
-    println!("Successfully wrote cat_children and cat_parents to Parquet files.");
+    println!(
+        "Successfully streamed {} records to cat_children.parquet and cat_parents.parquet",
+        total
+    );
 
     Ok(())
 }
@@ -1,6 +1,5 @@
 use byteorder::{LittleEndian, WriteBytesExt};
 use polars::prelude::*;
-use rayon::prelude::*;
 use std::fs::File;
 use std::io::Write;
 use std::io::{BufRead, BufReader, BufWriter};
@@ -61,87 +60,117 @@ fn get_file_size<P: AsRef<Path>>(path: P) -> std::io::Result<u64> {
     Ok(std::fs::metadata(path)?.len())
 }
 
+/// The output schema, fixed up front so row groups can be streamed out one
+/// at a time instead of inferred from a fully-collected `DataFrame`.
+fn output_schema() -> Schema {
+    Schema::from_iter([
+        Field::new("project".into(), DataType::String),
+        Field::new("page_id".into(), DataType::Int64),
+        Field::new("access_method".into(), DataType::Int64),
+        Field::new("daily_views".into(), DataType::Int64),
+    ])
+}
+
+/// Converts line-delimited pageview dump records into a parquet file.
+///
+/// Each `chunk_size` batch of parsed records is handed to the rayon pool as
+/// soon as it's read and turned into its own row group, written out by a
+/// single writer thread as soon as it's ready, instead of collecting every
+/// chunk's `DataFrame` in memory and concatenating them before one final
+/// write. The channel between the workers and the writer thread holds at
+/// most `worker_count` row groups: if the writer falls behind, workers
+/// block on `tx.send`. The reader does not wait for them and keeps spawning
+/// one task per chunk, so chunks read ahead of the writer stay queued in
+/// memory; peak memory is not bounded by `chunk_size * worker_count`.
 pub fn convert_pageviews_to_parquet(
     output_path: &str,
     chunk_size: usize,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Starting conversion...");
 
+    let worker_count = rayon::current_num_threads().max(1);
+    let schema = output_schema();
+
+    let file = File::create(output_path)?;
+    let writer = ParquetWriter::new(file)
+        .with_compression(ParquetCompression::Snappy)
+        // `project`/`access_method` only take a tiny handful of distinct
+        // values across a full day of pageviews; dictionary-encoding them
+        // keeps the on-disk footprint to the distinct values plus one small
+        // key per row instead of repeating the full value every row.
+        .with_dictionary_columns(vec!["project".to_string(), "access_method".to_string()]);
+    let mut batched_writer = writer.batched(&schema)?;
+
+    let (tx, rx) = std::sync::mpsc::sync_channel::<DataFrame>(worker_count);
+
+    let writer_handle = std::thread::spawn(move || -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut chunks_written = 0;
+        for df in rx {
+            batched_writer.write_batch(&df)?;
+            chunks_written += 1;
+        }
+        batched_writer.finish()?;
+        Ok(chunks_written)
+    });
+
     let stdin = std::io::stdin();
     let reader = BufReader::new(stdin.lock());
 
-    let mut chunks = Vec::new();
-    let mut current_chunk = Vec::with_capacity(chunk_size);
     let mut lines_processed = 0;
     let bytes_read = Arc::new(AtomicUsize::new(0));
+    let mut current_chunk = Vec::with_capacity(chunk_size);
 
-    println!("Reading and chunking data...");
-
-    for line in reader.lines() {
-        let line = line?;
-        let line_bytes = line.len() + 1; // +1 for newline
-        bytes_read.fetch_add(line_bytes, Ordering::Relaxed);
+    println!("Reading, processing, and streaming row groups...");
 
-        match parse_line(&line) {
-            Ok(record) => {
-                current_chunk.push(record);
-                lines_processed += 1;
+    rayon::scope(|scope| {
+        let spawn_chunk = |chunk: Vec<PageView>, tx: std::sync::mpsc::SyncSender<DataFrame>| {
+            scope.spawn(move |_| {
+                if let Ok(df) = process_chunk(chunk) {
+                    let _ = tx.send(df);
+                }
+            });
+        };
 
-                if current_chunk.len() >= chunk_size {
-                    chunks.push(current_chunk);
-                    current_chunk = Vec::with_capacity(chunk_size);
+        for line in reader.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => continue,
+            };
+            let line_bytes = line.len() + 1; // +1 for newline
+            bytes_read.fetch_add(line_bytes, Ordering::Relaxed);
+
+            match parse_line(&line) {
+                Ok(record) => {
+                    current_chunk.push(record);
+                    lines_processed += 1;
+
+                    if current_chunk.len() >= chunk_size {
+                        let chunk = std::mem::replace(&mut current_chunk, Vec::with_capacity(chunk_size));
+                        spawn_chunk(chunk, tx.clone());
+                    }
+                }
+                Err(_) => {
+                    // Silently skip malformed lines in production
+                    continue;
                 }
-            }
-            Err(_) => {
-                // Silently skip malformed lines in production
-                continue;
             }
         }
-    }
 
-    // Add remaining records
-    if !current_chunk.is_empty() {
-        chunks.push(current_chunk);
-    }
-
-    if chunks.is_empty() {
-        return Err("No valid data to write".into());
-    }
-
-    println!("\nProcessing {} chunks in parallel...", chunks.len());
-
-    let chunk_counter = Arc::new(AtomicUsize::new(0));
-
-    // Process chunks in parallel
-    let dataframes: Vec<DataFrame> = chunks
-        .into_par_iter()
-        .filter_map(|chunk| {
-            let result = process_chunk(chunk);
-
-            // Update progress
-            let count = chunk_counter.fetch_add(1, Ordering::Relaxed);
+        if !current_chunk.is_empty() {
+            spawn_chunk(current_chunk, tx.clone());
+        }
+    });
 
-            result.ok()
-        })
-        .collect();
+    drop(tx);
+    let chunks_written = writer_handle.join().map_err(|_| "writer thread panicked")??;
 
-    if dataframes.is_empty() {
-        return Err("No valid dataframes created".into());
+    if chunks_written == 0 {
+        return Err("No valid data to write".into());
     }
 
-    println!("\nCombining {} dataframes...", dataframes.len());
-    // Convert DataFrame to LazyFrame
-    let lazy_frames: Vec<LazyFrame> = dataframes.into_iter().map(|df| df.lazy()).collect();
-
-    let combined = concat(&lazy_frames, UnionArgs::default())?;
-    println!("Writing to parquet file {} ", &output_path);
-    let mut file = File::create(output_path)?;
-    let mut dataframe = combined.collect()?; // Collect LazyFrame into DataFrame
-    ParquetWriter::new(&mut file)
-        .with_compression(ParquetCompression::Snappy)
-        .finish(&mut dataframe)?; // Pass the DataFrame
-    println!("\nâœ“ Conversion complete!");
+    println!("\n✓ Conversion complete!");
     println!("  Lines processed: {}", lines_processed);
+    println!("  Row-group batches written: {}", chunks_written);
 
     Ok(())
 }
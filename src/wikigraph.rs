@@ -1,11 +1,57 @@
 use anyhow::Result;
+use fst::automaton::Levenshtein;
+use fst::{IntoStreamer, Map as FstMap, MapBuilder, Streamer};
+use memmap2::Mmap;
 use polars::prelude::*;
 use roaring::RoaringBitmap;
 use std::collections::HashMap;
 use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
 use std::path::Path;
 use std::time::Instant;
 
+const SNAPSHOT_MAGIC: &[u8; 8] = b"WKGRSNP1";
+const SNAPSHOT_VERSION: u16 = 1;
+/// Fixed header: magic (8) + version (2) + num_cat_nodes (4) + num_art_nodes (4).
+const SNAPSHOT_HEADER_LEN: usize = 18;
+/// One `(offset: u64, length: u64)` entry per section, in this order.
+const SNAPSHOT_SECTIONS: usize = 8;
+const SNAPSHOT_TABLE_LEN: usize = SNAPSHOT_SECTIONS * 16;
+
+/// Errors from reading a `WikiGraph` snapshot written by `save_snapshot`.
+/// Unlike `load_bin_file`'s `panic!`, a corrupt or foreign-version snapshot
+/// is reported to the caller instead of aborting the process.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "snapshot I/O error: {}", err),
+            SnapshotError::InvalidMagic => write!(f, "invalid WikiGraph snapshot magic"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported WikiGraph snapshot version: {}", version)
+            }
+            SnapshotError::Truncated => write!(f, "WikiGraph snapshot truncated before declared length"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
 /// The core high-performance graph structure.
 /// All internal logic uses "Dense IDs" (0..N), not the raw Wikipedia Page IDs.
 pub struct WikiGraph {
@@ -32,6 +78,14 @@ pub struct WikiGraph {
     pub art_dense_to_original: Vec<u32>,
     pub art_original_to_dense: HashMap<u32, u32>,
     pub art_names: Vec<String>,
+
+    // --- Fuzzy Name Search ---
+    // Sorted FST over normalized (trimmed, lowercased) category names,
+    // used only to stream Levenshtein-automaton candidates out of
+    // `search_categories`; the actual original IDs live in
+    // `cat_search_postings` since the FST requires unique keys.
+    cat_search_index: FstMap<Vec<u8>>,
+    cat_search_postings: HashMap<String, Vec<u32>>,
 }
 
 impl WikiGraph {
@@ -191,6 +245,355 @@ impl WikiGraph {
             Vec::new()
         }
     }
+
+    /// Resolves a free-text `query` to categories by name, tolerating up
+    /// to `max_edits` Levenshtein edits. Streams the query's Levenshtein
+    /// automaton against `cat_search_index` to collect candidate
+    /// normalized names in one sub-linear pass, then ranks them by
+    /// ascending edit distance (recomputed exactly, since the FST only
+    /// tells us a match is *within* `max_edits`, not its precise value)
+    /// and, as a tiebreaker, ascending name length - shorter names being
+    /// the more exact match for a given edit distance. Duplicate surface
+    /// forms (multiple categories normalizing to the same name) all come
+    /// back for a matching key. Returns (Original_Wiki_ID, Category_Name,
+    /// edit distance), truncated to `limit`.
+    pub fn search_categories(&self, query: &str, max_edits: u8, limit: usize) -> Vec<(u32, String, u8)> {
+        let normalized = normalize_search_key(query);
+        let automaton = match Levenshtein::new(&normalized, max_edits as u32) {
+            Ok(automaton) => automaton,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut matches: Vec<(u8, String)> = Vec::new();
+        let mut stream = self.cat_search_index.search(&automaton).into_stream();
+        while let Some((key, _value)) = stream.next() {
+            let key = String::from_utf8_lossy(key).into_owned();
+            let distance = levenshtein_distance(&normalized, &key).min(u8::MAX as usize) as u8;
+            matches.push((distance, key));
+        }
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.len().cmp(&b.1.len())));
+
+        matches
+            .into_iter()
+            .flat_map(|(distance, key)| {
+                self.cat_search_postings
+                    .get(&key)
+                    .into_iter()
+                    .flat_map(|ids| ids.iter().copied())
+                    .filter_map(move |original_id| {
+                        let dense = *self.cat_original_to_dense.get(&original_id)?;
+                        Some((original_id, self.cat_names[dense as usize].clone(), distance))
+                    })
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Writes this graph to `path` as a versioned snapshot: a fixed header
+    /// (magic, format version, category/article node counts) followed by a
+    /// section offset table and then the sections themselves - `children`,
+    /// `parents` and `article_cats` flattened to CSR `offsets`/`targets`
+    /// arrays instead of `Vec<Vec<u32>>`, the `cat_articles` `RoaringBitmap`s
+    /// via their own `serialize_into` format, and the dense<->original ID
+    /// and name tables. `cat_original_to_dense`/`art_original_to_dense`
+    /// aren't persisted at all - `open_mmapped` rebuilds them from the
+    /// (much smaller) dense_to_original arrays in one O(n) pass, which is
+    /// cheaper than writing and re-reading a full hash table.
+    pub fn save_snapshot(&self, path: &str) -> io::Result<()> {
+        let (children_offsets, children_targets) = flatten_adjacency(&self.children);
+        let (parents_offsets, parents_targets) = flatten_adjacency(&self.parents);
+        let (article_cats_offsets, article_cats_targets) = flatten_adjacency(&self.article_cats);
+
+        let children_section = csr_section_bytes(&children_offsets, &children_targets);
+        let parents_section = csr_section_bytes(&parents_offsets, &parents_targets);
+        let article_cats_section = csr_section_bytes(&article_cats_offsets, &article_cats_targets);
+        let bitmaps_section = bitmaps_section_bytes(&self.cat_articles);
+        let cat_dense_to_original_section = u32_slice_bytes(&self.cat_dense_to_original).to_vec();
+        let art_dense_to_original_section = u32_slice_bytes(&self.art_dense_to_original).to_vec();
+        let cat_names_section = strings_section_bytes(&self.cat_names);
+        let art_names_section = strings_section_bytes(&self.art_names);
+
+        let sections: [&[u8]; SNAPSHOT_SECTIONS] = [
+            &children_section,
+            &parents_section,
+            &article_cats_section,
+            &bitmaps_section,
+            &cat_dense_to_original_section,
+            &art_dense_to_original_section,
+            &cat_names_section,
+            &art_names_section,
+        ];
+
+        let mut file = File::create(path)?;
+        file.write_all(SNAPSHOT_MAGIC)?;
+        file.write_all(&SNAPSHOT_VERSION.to_ne_bytes())?;
+        file.write_all(&(self.cat_dense_to_original.len() as u32).to_ne_bytes())?;
+        file.write_all(&(self.art_dense_to_original.len() as u32).to_ne_bytes())?;
+
+        let mut offset = (SNAPSHOT_HEADER_LEN + SNAPSHOT_TABLE_LEN) as u64;
+        for section in &sections {
+            file.write_all(&offset.to_ne_bytes())?;
+            file.write_all(&(section.len() as u64).to_ne_bytes())?;
+            offset += section.len() as u64;
+        }
+
+        for section in &sections {
+            file.write_all(section)?;
+        }
+
+        Ok(())
+    }
+
+    /// Memory-maps a snapshot written by `save_snapshot` and rebuilds a
+    /// `WikiGraph` from it without touching Parquet, validating the
+    /// header before trusting the section table (returning a typed
+    /// `SnapshotError` rather than `panic!`-ing like `load_bin_file`).
+    pub fn open_mmapped(path: &str) -> Result<Self, SnapshotError> {
+        let file = File::open(path)?;
+        // Safety: treated as read-only for the mapping's lifetime; no
+        // guard against concurrent external writers.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < SNAPSHOT_HEADER_LEN + SNAPSHOT_TABLE_LEN || &mmap[0..8] != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+
+        let version = u16::from_ne_bytes(mmap[8..10].try_into().unwrap());
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let num_cat_nodes = u32::from_ne_bytes(mmap[10..14].try_into().unwrap()) as usize;
+        let num_art_nodes = u32::from_ne_bytes(mmap[14..18].try_into().unwrap()) as usize;
+
+        let mut section_bounds = [(0usize, 0usize); SNAPSHOT_SECTIONS];
+        for (i, bounds) in section_bounds.iter_mut().enumerate() {
+            let entry_start = SNAPSHOT_HEADER_LEN + i * 16;
+            let section_offset =
+                u64::from_ne_bytes(mmap[entry_start..entry_start + 8].try_into().unwrap()) as usize;
+            let section_len = u64::from_ne_bytes(
+                mmap[entry_start + 8..entry_start + 16].try_into().unwrap(),
+            ) as usize;
+            if section_offset + section_len > mmap.len() {
+                return Err(SnapshotError::Truncated);
+            }
+            *bounds = (section_offset, section_len);
+        }
+        let section = |i: usize| -> &[u8] {
+            let (start, len) = section_bounds[i];
+            &mmap[start..start + len]
+        };
+
+        let children = read_csr_section(section(0));
+        let parents = read_csr_section(section(1));
+        let article_cats = read_csr_section(section(2));
+        let cat_articles = read_bitmaps_section(section(3), num_cat_nodes);
+        let cat_dense_to_original = read_u32_array(section(4), num_cat_nodes);
+        let art_dense_to_original = read_u32_array(section(5), num_art_nodes);
+        let cat_names = read_strings_section(section(6), num_cat_nodes);
+        let art_names = read_strings_section(section(7), num_art_nodes);
+
+        let cat_original_to_dense = cat_dense_to_original
+            .iter()
+            .enumerate()
+            .map(|(dense, &original)| (original, dense as u32))
+            .collect();
+        let art_original_to_dense = art_dense_to_original
+            .iter()
+            .enumerate()
+            .map(|(dense, &original)| (original, dense as u32))
+            .collect();
+        let (cat_search_index, cat_search_postings) =
+            build_cat_search_index(&cat_dense_to_original, &cat_names);
+
+        Ok(WikiGraph {
+            children,
+            parents,
+            cat_articles,
+            article_cats,
+            cat_dense_to_original,
+            cat_original_to_dense,
+            cat_names,
+            art_dense_to_original,
+            art_original_to_dense,
+            art_names,
+            cat_search_index,
+            cat_search_postings,
+        })
+    }
+}
+
+/// Normalizes a name for fuzzy search: trimmed and lowercased, so casing
+/// and incidental whitespace don't count as edits.
+fn normalize_search_key(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// Classic O(len(a) * len(b)) edit distance, used to get `search_categories`
+/// an exact distance for ranking once the FST automaton has already done
+/// the sub-linear work of finding which candidates are within tolerance.
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Builds the sorted FST of normalized category names plus the side table
+/// mapping each normalized name back to every original category ID that
+/// normalizes to it (the FST itself requires unique keys).
+fn build_cat_search_index(
+    cat_dense_to_original: &[u32],
+    cat_names: &[String],
+) -> (FstMap<Vec<u8>>, HashMap<String, Vec<u32>>) {
+    let mut postings: HashMap<String, Vec<u32>> = HashMap::new();
+    for (dense, name) in cat_names.iter().enumerate() {
+        let key = normalize_search_key(name);
+        postings.entry(key).or_default().push(cat_dense_to_original[dense]);
+    }
+
+    let mut keys: Vec<&String> = postings.keys().collect();
+    keys.sort();
+
+    let mut builder = MapBuilder::new(Vec::new()).expect("in-memory FST builder cannot fail");
+    for key in keys {
+        builder
+            .insert(key.as_bytes(), 0)
+            .expect("keys are inserted in sorted order");
+    }
+    let bytes = builder.into_inner().expect("in-memory FST builder cannot fail");
+    let index = FstMap::new(bytes).expect("just-built FST bytes are valid");
+
+    (index, postings)
+}
+
+/// Flattens a `Vec<Vec<u32>>` adjacency list into a CSR `(offsets, targets)`
+/// pair: `offsets[i]..offsets[i+1]` indexes into `targets` for node `i`.
+fn flatten_adjacency(adj: &[Vec<u32>]) -> (Vec<u32>, Vec<u32>) {
+    let mut offsets = Vec::with_capacity(adj.len() + 1);
+    let mut targets = Vec::new();
+    offsets.push(0u32);
+    for neighbors in adj {
+        targets.extend_from_slice(neighbors);
+        offsets.push(targets.len() as u32);
+    }
+    (offsets, targets)
+}
+
+/// Reinterprets a `[u32]` slice's bytes without copying.
+fn u32_slice_bytes(data: &[u32]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
+
+/// A CSR section is self-framed (`num_offsets`, `num_targets` followed by
+/// the raw arrays) since, unlike the other sections, its byte length alone
+/// doesn't tell a reader where `offsets` ends and `targets` begins.
+fn csr_section_bytes(offsets: &[u32], targets: &[u32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8 + offsets.len() * 4 + targets.len() * 4);
+    bytes.extend_from_slice(&(offsets.len() as u32).to_ne_bytes());
+    bytes.extend_from_slice(&(targets.len() as u32).to_ne_bytes());
+    bytes.extend_from_slice(u32_slice_bytes(offsets));
+    bytes.extend_from_slice(u32_slice_bytes(targets));
+    bytes
+}
+
+fn read_csr_section(bytes: &[u8]) -> Vec<Vec<u32>> {
+    let num_offsets = u32::from_ne_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    let num_targets = u32::from_ne_bytes(bytes[4..8].try_into().unwrap()) as usize;
+    let offsets_start = 8;
+    let offsets_end = offsets_start + num_offsets * 4;
+    let targets_end = offsets_end + num_targets * 4;
+
+    let offsets: Vec<u32> = bytes[offsets_start..offsets_end]
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect();
+    let targets: Vec<u32> = bytes[offsets_end..targets_end]
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect();
+
+    let num_nodes = num_offsets.saturating_sub(1);
+    let mut adjacency = Vec::with_capacity(num_nodes);
+    for i in 0..num_nodes {
+        adjacency.push(targets[offsets[i] as usize..offsets[i + 1] as usize].to_vec());
+    }
+    adjacency
+}
+
+fn bitmaps_section_bytes(bitmaps: &[RoaringBitmap]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(bitmaps.len() as u32).to_ne_bytes());
+    for bitmap in bitmaps {
+        let mut serialized = Vec::new();
+        bitmap
+            .serialize_into(&mut serialized)
+            .expect("serializing a RoaringBitmap into a Vec cannot fail");
+        bytes.extend_from_slice(&(serialized.len() as u32).to_ne_bytes());
+        bytes.extend_from_slice(&serialized);
+    }
+    bytes
+}
+
+fn read_bitmaps_section(bytes: &[u8], expected_count: usize) -> Vec<RoaringBitmap> {
+    let count = u32::from_ne_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    debug_assert_eq!(count, expected_count);
+    let mut cursor = 4;
+    let mut bitmaps = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = u32::from_ne_bytes(bytes[cursor..cursor + 4].try_into().unwrap()) as usize;
+        cursor += 4;
+        let bitmap = RoaringBitmap::deserialize_from(&bytes[cursor..cursor + len])
+            .expect("malformed RoaringBitmap in snapshot");
+        bitmaps.push(bitmap);
+        cursor += len;
+    }
+    bitmaps
+}
+
+fn read_u32_array(bytes: &[u8], expected_count: usize) -> Vec<u32> {
+    let values: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect();
+    debug_assert_eq!(values.len(), expected_count);
+    values
+}
+
+fn strings_section_bytes(names: &[String]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(names.len() as u32).to_ne_bytes());
+    for name in names {
+        let name_bytes = name.as_bytes();
+        bytes.extend_from_slice(&(name_bytes.len() as u32).to_ne_bytes());
+        bytes.extend_from_slice(name_bytes);
+    }
+    bytes
+}
+
+fn read_strings_section(bytes: &[u8], expected_count: usize) -> Vec<String> {
+    let count = u32::from_ne_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    debug_assert_eq!(count, expected_count);
+    let mut cursor = 4;
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = u32::from_ne_bytes(bytes[cursor..cursor + 4].try_into().unwrap()) as usize;
+        cursor += 4;
+        let name = String::from_utf8_lossy(&bytes[cursor..cursor + len]).into_owned();
+        names.push(name);
+        cursor += len;
+    }
+    names
 }
 
 pub struct GraphBuilder;
@@ -297,6 +700,9 @@ impl GraphBuilder {
 
         println!("Graph build completed in {:.2?}s", start.elapsed());
 
+        let (cat_search_index, cat_search_postings) =
+            build_cat_search_index(&cat_dense_to_original, &cat_names);
+
         Ok(WikiGraph {
             children,
             parents,
@@ -308,6 +714,8 @@ impl GraphBuilder {
             art_dense_to_original,
             art_original_to_dense,
             art_names,
+            cat_search_index,
+            cat_search_postings,
         })
     }
 
@@ -1,8 +1,9 @@
+use parquet::bloom_filter::Sbbf;
+use parquet::file::reader::{FileReader, SerializedFileReader};
 use parquet::file::writer::SerializedFileWriter;
 use parquet::{file::properties::WriterProperties, record::RecordWriter as _};
 use parquet_derive::ParquetRecordWriter;
 use polars::prelude::{LazyFrame, PlPath};
-use std::collections::HashSet;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
@@ -15,6 +16,48 @@ struct ArticleCategory {
     category_qid: u32,
 }
 
+/// One Parquet file's per-row-group split-block bloom filters on `page_id`,
+/// loaded straight from the footer, so a definite "not present" can be
+/// answered without a separate `HashSet<u32>` of every `page_id` in the
+/// file - the redundant structure the old filtering pass built purely for
+/// membership checks, on top of the `id_to_qid` map that already needed to
+/// be resident for the subsequent qid lookup. `id_to_qid` itself is still
+/// collected in full up front; this only removes the duplicate of its keys.
+struct PageIdBloomFilter {
+    row_groups: Vec<Option<Sbbf>>,
+}
+
+impl PageIdBloomFilter {
+    fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let file = File::open(path)?;
+        let reader = SerializedFileReader::new(file)?;
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let page_id_col = schema
+            .columns()
+            .iter()
+            .position(|c| c.name() == "page_id")
+            .ok_or("page_id column missing from schema")?;
+
+        let mut row_groups = Vec::with_capacity(reader.metadata().num_row_groups());
+        for rg_idx in 0..reader.metadata().num_row_groups() {
+            let row_group_reader = reader.get_row_group(rg_idx)?;
+            row_groups.push(row_group_reader.get_column_bloom_filter(page_id_col).cloned());
+        }
+        Ok(Self { row_groups })
+    }
+
+    /// `false` only when every row group's bloom filter rules `page_id`
+    /// out - a definite absence. `true` otherwise, including when the file
+    /// carries no bloom filter at all, in which case the id->qid map lookup
+    /// that follows is the real membership check.
+    fn maybe_contains(&self, page_id: u32) -> bool {
+        self.row_groups.iter().any(|bloom| match bloom {
+            Some(bloom) => bloom.check(&(page_id as i32)),
+            None => true,
+        })
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
     if args.len() < 3 {
@@ -43,7 +86,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .zip(article_qids)
         .filter_map(|(id, qid)| Some((id?, qid?)))
         .collect();
-    let valid_article_ids_set: HashSet<u32> = article_id_to_qid.keys().into_iter().collect();
+    let article_bloom = PageIdBloomFilter::load(articles_parquet)?;
 
     let categories_parquet_path: PlPath = PlPath::Local(Arc::from(Path::new(&categories_parquet)));
     let categories_df =
@@ -57,8 +100,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .zip(category_qids)
         .filter_map(|(id, qid)| Some((id?, qid?)))
         .collect();
-
-    let valid_category_ids_set: HashSet<u32> = category_id_to_qid.keys().into_iter().collect();
+    let category_bloom = PageIdBloomFilter::load(categories_parquet)?;
 
     let mut record_count = 0;
     let mut lines_count = 0;
@@ -72,9 +114,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let article_id = parts.next()?.parse::<u32>().ok()?;
             let category_id = parts.next()?.parse::<u32>().ok()?;
 
-            if !valid_article_ids_set.contains(&article_id)
-                || !valid_category_ids_set.contains(&category_id)
-            {
+            // Bloom filter first: a definite "not in this file" skips the
+            // id->qid lookup entirely. A "maybe" still needs that lookup to
+            // confirm, since bloom filters can false-positive.
+            if !article_bloom.maybe_contains(article_id) || !category_bloom.maybe_contains(category_id) {
                 return None;
             }
             let article_qid = article_id_to_qid.get(article_id)?;
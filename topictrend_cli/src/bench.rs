@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::{Duration, Instant};
+use topictrend::pageview_engine::PageViewEngine;
+
+/// One operation in a workload file. `op` selects the variant via serde's
+/// internally-tagged representation, so a workload JSON array looks like
+/// `[{"op":"category-trend","category_qid":1,"depth":2,"start":"...","end":"..."}, ...]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum WorkloadOp {
+    CategoryTrend {
+        category_qid: u32,
+        #[serde(default)]
+        depth: u32,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    },
+    TopCategories {
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+        n: usize,
+    },
+    AnalyzeDepth {
+        category_qid: u32,
+    },
+}
+
+impl WorkloadOp {
+    fn label(&self) -> String {
+        match self {
+            WorkloadOp::CategoryTrend { category_qid, .. } => {
+                format!("category-trend({})", category_qid)
+            }
+            WorkloadOp::TopCategories { n, .. } => format!("top-categories({})", n),
+            WorkloadOp::AnalyzeDepth { category_qid } => {
+                format!("analyze-depth({})", category_qid)
+            }
+        }
+    }
+
+    /// Runs the operation once against `engine`, discarding its result -
+    /// only the wall-clock cost is of interest here.
+    fn run(&self, engine: &PageViewEngine) {
+        match self {
+            WorkloadOp::CategoryTrend {
+                category_qid,
+                depth,
+                start,
+                end,
+            } => {
+                engine.get_category_trend(*category_qid, *depth, *start, *end);
+            }
+            WorkloadOp::TopCategories { start, end, n } => {
+                let _ = engine.get_top_categories(*start, *end, *n);
+            }
+            WorkloadOp::AnalyzeDepth { category_qid } => {
+                engine.get_wikigraph().analyze_depth_from_root(*category_qid);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpLatencies {
+    pub label: String,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub mean_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub wiki: String,
+    pub graph_build_ms: f64,
+    pub warmup: usize,
+    pub repeat: usize,
+    pub operations: Vec<OpLatencies>,
+}
+
+fn percentile_ms(mut samples: Vec<Duration>, p: f64) -> f64 {
+    samples.sort();
+    let idx = ((samples.len() - 1) as f64 * p).round() as usize;
+    samples[idx].as_secs_f64() * 1000.0
+}
+
+fn summarize(label: String, samples: Vec<Duration>) -> OpLatencies {
+    let mean_ms =
+        samples.iter().map(Duration::as_secs_f64).sum::<f64>() / samples.len() as f64 * 1000.0;
+    OpLatencies {
+        label,
+        min_ms: percentile_ms(samples.clone(), 0.0),
+        median_ms: percentile_ms(samples.clone(), 0.5),
+        p95_ms: percentile_ms(samples.clone(), 0.95),
+        mean_ms,
+    }
+}
+
+/// Loads `workload_path`, builds the `wiki` graph (timed separately), then
+/// runs every op `warmup` times (discarded) followed by `repeat` timed
+/// times, returning a report of per-operation latency statistics.
+pub fn run_workload(
+    wiki: &str,
+    workload_path: &str,
+    warmup: usize,
+    repeat: usize,
+) -> Result<BenchReport, Box<dyn std::error::Error>> {
+    let workload_json = fs::read_to_string(workload_path)?;
+    let ops: Vec<WorkloadOp> = serde_json::from_str(&workload_json)?;
+
+    let build_start = Instant::now();
+    let engine = PageViewEngine::new(wiki);
+    let graph_build_ms = build_start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut operations = Vec::with_capacity(ops.len());
+    for op in &ops {
+        for _ in 0..warmup {
+            op.run(&engine);
+        }
+
+        let mut samples = Vec::with_capacity(repeat);
+        for _ in 0..repeat {
+            let start = Instant::now();
+            op.run(&engine);
+            samples.push(start.elapsed());
+        }
+
+        operations.push(summarize(op.label(), samples));
+    }
+
+    Ok(BenchReport {
+        wiki: wiki.to_string(),
+        graph_build_ms,
+        warmup,
+        repeat,
+        operations,
+    })
+}
+
+/// Loads a previously-saved `BenchReport` and prints a per-operation delta
+/// table against `current`, flagging any operation whose median latency
+/// regressed by more than `threshold_pct` percent. Operations are matched
+/// by label; ops present in only one report are reported as added/removed.
+pub fn print_baseline_diff(baseline_path: &str, current: &BenchReport, threshold_pct: f64) {
+    let baseline: BenchReport = match fs::read_to_string(baseline_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+    {
+        Some(report) => report,
+        None => {
+            eprintln!("Could not read baseline report at {}", baseline_path);
+            return;
+        }
+    };
+
+    println!(
+        "{:<30} {:>12} {:>12} {:>10}",
+        "operation", "baseline_ms", "current_ms", "delta_%"
+    );
+    for current_op in &current.operations {
+        let Some(baseline_op) = baseline
+            .operations
+            .iter()
+            .find(|op| op.label == current_op.label)
+        else {
+            println!("{:<30} {:>12} {:>12} {:>10}", current_op.label, "-", "added", "-");
+            continue;
+        };
+
+        let delta_pct = if baseline_op.median_ms > 0.0 {
+            (current_op.median_ms - baseline_op.median_ms) / baseline_op.median_ms * 100.0
+        } else {
+            0.0
+        };
+        let flag = if delta_pct > threshold_pct {
+            " ⚠ REGRESSION"
+        } else {
+            ""
+        };
+        println!(
+            "{:<30} {:>12.3} {:>12.3} {:>9.1}%{}",
+            current_op.label, baseline_op.median_ms, current_op.median_ms, delta_pct, flag
+        );
+    }
+    for baseline_op in &baseline.operations {
+        if !current.operations.iter().any(|op| op.label == baseline_op.label) {
+            println!(
+                "{:<30} {:>12.3} {:>12} {:>10}",
+                baseline_op.label, baseline_op.median_ms, "removed", "-"
+            );
+        }
+    }
+}
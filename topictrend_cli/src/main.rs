@@ -1,9 +1,68 @@
 use clap::{Arg, ArgMatches, Command};
 use std::error::Error;
+use std::io::BufRead;
 use topictrend::{graphbuilder::GraphBuilder, pageview_engine::PageViewEngine, wikigraph};
 
+mod bench;
 mod pageviews;
 
+/// A single result row, shared across the `list-*`/`category-trend`
+/// handlers so `--format json`/`--format ndjson` can serialize every
+/// command's output the same way instead of each handler inventing its own
+/// ad-hoc tuple shape. Fields that don't apply to a given command (e.g.
+/// `depth` for `list-parent-categories`) are left `None` rather than
+/// omitted, so every line has the same schema in `ndjson` mode.
+#[derive(Debug, Clone, serde::Serialize)]
+struct OutputItem {
+    id: Option<u32>,
+    name: Option<String>,
+    depth: Option<u8>,
+    total_views: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+}
+
+impl OutputFormat {
+    fn parse(raw: &str) -> Self {
+        match raw {
+            "json" => OutputFormat::Json,
+            "ndjson" => OutputFormat::Ndjson,
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+/// Prints `items` in `format`; for `Text`, `text_line` renders each item as
+/// the handler's existing human-readable line.
+fn print_items(items: &[OutputItem], format: OutputFormat, text_line: impl Fn(&OutputItem) -> String) {
+    match format {
+        OutputFormat::Text => {
+            for item in items {
+                println!("{}", text_line(item));
+            }
+        }
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(items).expect("Failed to serialize results")
+            );
+        }
+        OutputFormat::Ndjson => {
+            for item in items {
+                println!(
+                    "{}",
+                    serde_json::to_string(item).expect("Failed to serialize result")
+                );
+            }
+        }
+    }
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Define the CLI structure
     let matches = Command::new("WikiGraph CLI")
@@ -15,6 +74,15 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .default_value("enwiki")
                 .help("Wikipedia code. Example enwiki, eswiki, hiwiki etc"),
         )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(["text", "json", "ndjson"])
+                .default_value("text")
+                .global(true)
+                .help("Output format for structured subcommands: one human-readable line per \
+                       result, one JSON array, or one JSON object per line"),
+        )
         .subcommand(
             Command::new("list-articles")
                 .about("Retrieve all articles in a category")
@@ -124,29 +192,142 @@ fn main() -> Result<(), Box<dyn Error>> {
                         .help("End date in YYYY-MM-DD format"),
                 ),
         )
+        .subcommand(
+            Command::new("shortest-category-path")
+                .about("Find the shortest category path between two categories")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The QID of the starting category"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The QID of the target category"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .short('d')
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Maximum combined path length to search (unbounded if omitted)"),
+                ),
+        )
+        .subcommand(
+            Command::new("common-ancestors")
+                .about("Find categories common to both given categories, nearest first")
+                .arg(
+                    Arg::new("a")
+                        .long("a")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The QID of the first category"),
+                )
+                .arg(
+                    Arg::new("b")
+                        .long("b")
+                        .required(true)
+                        .value_parser(clap::value_parser!(u32))
+                        .help("The QID of the second category"),
+                )
+                .arg(
+                    Arg::new("depth")
+                        .long("depth")
+                        .short('d')
+                        .value_parser(clap::value_parser!(u8))
+                        .help("Maximum distance to climb from each category (unbounded if omitted)"),
+                ),
+        )
+        .subcommand(
+            Command::new("batch")
+                .about(
+                    "Resolve many newline-delimited JSON requests against a single loaded \
+                     graph, one JSON response per line on stdout",
+                ),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Run a JSON workload file against the engine and report latency stats")
+                .arg(
+                    Arg::new("workload")
+                        .long("workload")
+                        .short('f')
+                        .required(true)
+                        .help("Path to a JSON workload file"),
+                )
+                .arg(
+                    Arg::new("warmup")
+                        .long("warmup")
+                        .default_value("3")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Untimed warmup runs per operation"),
+                )
+                .arg(
+                    Arg::new("repeat")
+                        .long("repeat")
+                        .default_value("10")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Timed runs per operation"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Path to write the JSON report to (stdout if omitted)"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .help("Path to a previous report.json to diff against"),
+                )
+                .arg(
+                    Arg::new("threshold")
+                        .long("threshold")
+                        .default_value("10.0")
+                        .value_parser(clap::value_parser!(f64))
+                        .help("Regression threshold in percent for --baseline"),
+                ),
+        )
         .get_matches();
 
     let wiki_id: &str = matches.get_one::<String>("wiki").unwrap();
+    let format = OutputFormat::parse(matches.get_one::<String>("format").unwrap());
     let graph_builder = GraphBuilder::new(wiki_id);
     let graph = graph_builder.build().expect("Error while building graph");
 
     // Dispatch subcommands
     match matches.subcommand() {
-        Some(("list-articles", sub_m)) => handle_get_articles(&graph, sub_m),
-        Some(("list-child-categories", sub_m)) => handle_get_child_categories(&graph, sub_m),
+        Some(("list-articles", sub_m)) => handle_get_articles(&graph, sub_m, format),
+        Some(("list-child-categories", sub_m)) => {
+            handle_get_child_categories(&graph, sub_m, format)
+        }
         Some(("list-descendant-categories", sub_m)) => {
-            handle_get_descendant_categories(&graph, sub_m)
+            handle_get_descendant_categories(&graph, sub_m, format)
+        }
+        Some(("list-parent-categories", sub_m)) => {
+            handle_get_parent_categories(&graph, sub_m, format)
+        }
+        Some(("list-article-categories", sub_m)) => {
+            handle_get_article_categories(&graph, sub_m, format)
+        }
+        Some(("category-trend", sub_m)) => handle_category_trend(wiki_id, sub_m, format),
+        Some(("shortest-category-path", sub_m)) => {
+            handle_shortest_category_path(&graph, sub_m, format)
         }
-        Some(("list-parent-categories", sub_m)) => handle_get_parent_categories(&graph, sub_m),
-        Some(("list-article-categories", sub_m)) => handle_get_article_categories(&graph, sub_m),
-        Some(("category-trend", sub_m)) => handle_category_trend(wiki_id, sub_m),
+        Some(("common-ancestors", sub_m)) => handle_common_ancestors(&graph, sub_m, format),
+        Some(("batch", _)) => handle_batch(&graph),
+        Some(("bench", sub_m)) => handle_bench(wiki_id, sub_m),
         _ => println!("No valid subcommand provided. Use --help for usage."),
     }
 
     Ok(())
 }
 
-fn handle_get_articles(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+fn handle_get_articles(graph: &wikigraph::WikiGraph, matches: &ArgMatches, format: OutputFormat) {
     let category_title: &String = matches.get_one::<String>("category").unwrap();
     let depth: &u8 = matches.get_one::<u8>("depth").unwrap();
 
@@ -157,21 +338,36 @@ fn handle_get_articles(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
             std::process::exit(1);
         }
     };
-    println!(
-        "Found {} articles in category {} (depth {}).",
-        articles.len(),
-        category_title,
-        depth
-    );
-
-    for article_id in articles.iter().take(10) {
-        if let Some(name) = graph.get_article_name(article_id) {
-            println!(" - {}", name);
-        }
+
+    if format == OutputFormat::Text {
+        println!(
+            "Found {} articles in category {} (depth {}).",
+            articles.len(),
+            category_title,
+            depth
+        );
     }
+
+    let items: Vec<OutputItem> = articles
+        .iter()
+        .map(|article_id| OutputItem {
+            id: Some(article_id),
+            name: graph.get_article_name(article_id),
+            depth: None,
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(" - {}", item.name.as_deref().unwrap_or("<unknown>"))
+    });
 }
 
-fn handle_get_child_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+fn handle_get_child_categories(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
     let category_title: &String = matches.get_one::<String>("category").unwrap();
 
     let children = match graph.get_child_categories(category_title) {
@@ -181,18 +377,39 @@ fn handle_get_child_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatche
             std::process::exit(1);
         }
     };
-    println!(
-        "Found {} child categories for category {}.",
-        children.len(),
-        category_title
-    );
-
-    for (id, name) in children {
-        println!(" - {}: {}", id, name);
+
+    if format == OutputFormat::Text {
+        println!(
+            "Found {} child categories for category {}.",
+            children.len(),
+            category_title
+        );
     }
+
+    let items: Vec<OutputItem> = children
+        .into_iter()
+        .map(|(id, name)| OutputItem {
+            id: Some(id),
+            name: Some(name),
+            depth: None,
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(
+            " - {}: {}",
+            item.id.unwrap_or_default(),
+            item.name.as_deref().unwrap_or("<unknown>")
+        )
+    });
 }
 
-fn handle_get_descendant_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+fn handle_get_descendant_categories(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
     let category_title: &String = matches.get_one::<String>("category").unwrap();
     let depth: &u8 = matches.get_one::<u8>("depth").unwrap();
 
@@ -203,19 +420,41 @@ fn handle_get_descendant_categories(graph: &wikigraph::WikiGraph, matches: &ArgM
             std::process::exit(1);
         }
     };
-    println!(
-        "Found {} descendant categories for category {} (depth {}).",
-        descendants.len(),
-        category_title,
-        depth
-    );
-
-    for (id, name, d) in descendants {
-        println!(" - {}: {} (depth {})", id, name, d);
+
+    if format == OutputFormat::Text {
+        println!(
+            "Found {} descendant categories for category {} (depth {}).",
+            descendants.len(),
+            category_title,
+            depth
+        );
     }
+
+    let items: Vec<OutputItem> = descendants
+        .into_iter()
+        .map(|(id, name, d)| OutputItem {
+            id: Some(id),
+            name: Some(name),
+            depth: Some(d),
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(
+            " - {}: {} (depth {})",
+            item.id.unwrap_or_default(),
+            item.name.as_deref().unwrap_or("<unknown>"),
+            item.depth.unwrap_or_default()
+        )
+    });
 }
 
-fn handle_get_parent_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+fn handle_get_parent_categories(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
     let category_title: &String = matches.get_one::<String>("category").unwrap();
 
     let parents = match graph.get_parent_categories(category_title) {
@@ -225,18 +464,35 @@ fn handle_get_parent_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatch
             std::process::exit(1);
         }
     };
-    println!(
-        "Found {} parent categories for category {}.",
-        parents.len(),
-        category_title
-    );
-
-    for id in parents {
-        println!(" - {}", id);
+
+    if format == OutputFormat::Text {
+        println!(
+            "Found {} parent categories for category {}.",
+            parents.len(),
+            category_title
+        );
     }
+
+    let items: Vec<OutputItem> = parents
+        .into_iter()
+        .map(|id| OutputItem {
+            id: Some(id),
+            name: None,
+            depth: None,
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(" - {}", item.id.unwrap_or_default())
+    });
 }
 
-fn handle_get_article_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatches) {
+fn handle_get_article_categories(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
     let article_id: &u32 = matches.get_one::<u32>("article-id").unwrap();
 
     let categories = match graph.get_categories_for_article(*article_id) {
@@ -246,18 +502,110 @@ fn handle_get_article_categories(graph: &wikigraph::WikiGraph, matches: &ArgMatc
             std::process::exit(1);
         }
     };
-    println!(
-        "Found {} categories for article {}.",
-        categories.len(),
-        article_id
-    );
-
-    for (id, name) in categories {
-        println!(" - {}: {}", id, name);
+
+    if format == OutputFormat::Text {
+        println!(
+            "Found {} categories for article {}.",
+            categories.len(),
+            article_id
+        );
     }
+
+    let items: Vec<OutputItem> = categories
+        .into_iter()
+        .map(|(id, name)| OutputItem {
+            id: Some(id),
+            name: Some(name),
+            depth: None,
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(
+            " - {}: {}",
+            item.id.unwrap_or_default(),
+            item.name.as_deref().unwrap_or("<unknown>")
+        )
+    });
 }
 
-fn handle_category_trend(wiki_id: &str, matches: &ArgMatches) {
+/// Reads newline-delimited JSON [`wikigraph::BatchRequest`]s from stdin and
+/// writes one JSON [`wikigraph::BatchResult`] per line to stdout, in the
+/// same order, reusing the single `graph` already loaded for this process
+/// instead of spawning one CLI invocation per lookup. A line that fails to
+/// parse gets an `error` result rather than aborting the whole batch.
+fn handle_batch(graph: &wikigraph::WikiGraph) {
+    let stdin = std::io::stdin();
+
+    let mut requests: Vec<Option<wikigraph::BatchRequest>> = Vec::new();
+    let mut parse_errors: Vec<Option<String>> = Vec::new();
+
+    for line in stdin.lock().lines() {
+        let line = line.expect("Failed to read stdin");
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<wikigraph::BatchRequest>(&line) {
+            Ok(request) => {
+                requests.push(Some(request));
+                parse_errors.push(None);
+            }
+            Err(err) => {
+                requests.push(None);
+                parse_errors.push(Some(err.to_string()));
+            }
+        }
+    }
+
+    let valid_requests: Vec<wikigraph::BatchRequest> =
+        requests.iter().filter_map(|r| r.clone()).collect();
+    let mut results = graph.run_batch(&valid_requests).into_iter();
+
+    for (request, parse_error) in requests.iter().zip(parse_errors.iter()) {
+        let result = match (request, parse_error) {
+            (Some(_), None) => results.next().expect("batch result count mismatch"),
+            (None, Some(message)) => wikigraph::BatchResult::Error {
+                message: message.clone(),
+            },
+            _ => unreachable!(),
+        };
+        println!(
+            "{}",
+            serde_json::to_string(&result).expect("Failed to serialize batch result")
+        );
+    }
+}
+
+fn handle_bench(wiki_id: &str, matches: &ArgMatches) {
+    let workload_path: &String = matches.get_one::<String>("workload").unwrap();
+    let warmup: &usize = matches.get_one::<usize>("warmup").unwrap();
+    let repeat: &usize = matches.get_one::<usize>("repeat").unwrap();
+
+    let report = match bench::run_workload(wiki_id, workload_path, *warmup, *repeat) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("Error running workload: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let report_json = serde_json::to_string_pretty(&report).expect("Failed to serialize report");
+    match matches.get_one::<String>("output") {
+        Some(path) => {
+            std::fs::write(path, &report_json).expect("Failed to write report");
+            println!("Wrote report to {}", path);
+        }
+        None => println!("{}", report_json),
+    }
+
+    if let Some(baseline_path) = matches.get_one::<String>("baseline") {
+        let threshold: &f64 = matches.get_one::<f64>("threshold").unwrap();
+        bench::print_baseline_diff(baseline_path, &report, *threshold);
+    }
+}
+
+fn handle_category_trend(wiki_id: &str, matches: &ArgMatches, format: OutputFormat) {
     let category: &String = matches.get_one::<String>("category").unwrap();
     let depth: &u8 = matches.get_one::<u8>("depth").unwrap();
     let start_date = matches
@@ -272,12 +620,105 @@ fn handle_category_trend(wiki_id: &str, matches: &ArgMatches) {
     let mut engine = PageViewEngine::new(wiki_id);
     let raw_data = engine.get_category_trend(category, *depth, start_date, end_date);
 
-    println!(
-        "Category trend for category {} (depth {}, start: {}, end: {}):",
-        category, depth, start_date, end_date
-    );
+    if format == OutputFormat::Text {
+        println!(
+            "Category trend for category {} (depth {}, start: {}, end: {}):",
+            category, depth, start_date, end_date
+        );
+    }
+
+    // Dates aren't dense/original IDs, so `id`/`depth` don't apply here -
+    // each day's date goes in `name`, its view count in `total_views`.
+    let items: Vec<OutputItem> = raw_data
+        .into_iter()
+        .map(|(date, views)| OutputItem {
+            id: None,
+            name: Some(date.to_string()),
+            depth: None,
+            total_views: Some(views),
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(
+            " - {}: {} views",
+            item.name.as_deref().unwrap_or("<unknown>"),
+            item.total_views.unwrap_or_default()
+        )
+    });
+}
+
+fn handle_shortest_category_path(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
+    let from: &u32 = matches.get_one::<u32>("from").unwrap();
+    let to: &u32 = matches.get_one::<u32>("to").unwrap();
+    let max_depth = matches.get_one::<u8>("depth").copied();
+
+    let path = graph.shortest_category_path(*from, *to, true, max_depth);
+
+    let items: Vec<OutputItem> = match &path {
+        Some(path) => path
+            .iter()
+            .enumerate()
+            .map(|(position, &qid)| OutputItem {
+                id: Some(qid),
+                name: None,
+                depth: Some(position as u8),
+                total_views: None,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    if format == OutputFormat::Text {
+        match &path {
+            Some(path) => println!(
+                "Shortest path from {} to {}: {}",
+                from,
+                to,
+                path.iter().map(u32::to_string).collect::<Vec<_>>().join(" -> ")
+            ),
+            None => println!("No path found from {} to {}.", from, to),
+        }
+        return;
+    }
+
+    print_items(&items, format, |_| String::new());
+}
+
+fn handle_common_ancestors(
+    graph: &wikigraph::WikiGraph,
+    matches: &ArgMatches,
+    format: OutputFormat,
+) {
+    let a: &u32 = matches.get_one::<u32>("a").unwrap();
+    let b: &u32 = matches.get_one::<u32>("b").unwrap();
+    let max_depth = matches.get_one::<u8>("depth").copied();
 
-    for trend in raw_data {
-        println!(" - {}: {} views", trend.0, trend.1);
+    let ancestors = graph.common_ancestors(*a, *b, max_depth);
+
+    if format == OutputFormat::Text {
+        println!("Common ancestors of {} and {} (nearest first):", a, b);
     }
+
+    let items: Vec<OutputItem> = ancestors
+        .into_iter()
+        .map(|(qid, combined_depth)| OutputItem {
+            id: Some(qid),
+            name: None,
+            depth: Some(combined_depth as u8),
+            total_views: None,
+        })
+        .collect();
+
+    print_items(&items, format, |item| {
+        format!(
+            " - {} (combined depth {})",
+            item.id.unwrap_or_default(),
+            item.depth.unwrap_or_default()
+        )
+    });
 }
@@ -1,12 +1,18 @@
+use flate2::read::GzDecoder;
 use polars::prelude::*;
-use rayon::prelude::*;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use topictrend::direct_map::DirectMap;
 
+/// Rows per row group in the output parquet file. Kept modest (rather than
+/// one giant row group) so a qid-sorted file yields many row groups, each
+/// with a narrow `[min_qid, max_qid]` range that `read_pageviews_for_qids`
+/// can skip past for a narrow category query.
+const PARQUET_ROW_GROUP_SIZE: usize = 100_000;
+
 #[derive(Debug, Clone)]
 struct PageView {
     wiki: String,
@@ -15,12 +21,46 @@ struct PageView {
     daily_views: u32,
 }
 
+/// Resolves a `--compression` value to the matching polars compression
+/// codec. Defaults to Snappy (the prior hardcoded behavior) for an unknown
+/// or missing value.
+fn parse_compression(name: Option<&str>) -> ParquetCompression {
+    match name {
+        Some("zstd") => ParquetCompression::Zstd(None),
+        Some("lz4") => ParquetCompression::Lz4Raw,
+        _ => ParquetCompression::Snappy,
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+    // --bloom-filter, --compression <name>, and --input <path> (repeatable)
+    // are optional flags that can appear anywhere after the positional
+    // args; strip them out before parsing those positionally.
+    let mut args: Vec<String> = std::env::args().collect();
+    let bloom_filter = match args.iter().position(|a| a == "--bloom-filter") {
+        Some(idx) => {
+            args.remove(idx);
+            true
+        }
+        None => false,
+    };
+    let compression_name = match args.iter().position(|a| a == "--compression") {
+        Some(idx) => {
+            args.remove(idx);
+            Some(args.remove(idx))
+        }
+        None => None,
+    };
+    let mut input_paths = Vec::new();
+    while let Some(idx) = args.iter().position(|a| a == "--input") {
+        args.remove(idx);
+        input_paths.push(args.remove(idx));
+    }
 
     if args.len() < 3 {
         eprintln!(
-            "Usage: {} <articles_parquet> <output_file> [chunk_size]",
+            "Usage: {} <articles_parquet> <output_file> [chunk_size] [--bloom-filter] \
+             [--compression {{snappy,zstd,lz4}}] [--input path.txt[.gz] ...]",
             args[0]
         );
         std::process::exit(1);
@@ -33,11 +73,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     } else {
         100_000
     };
+    let compression = parse_compression(compression_name.as_deref());
 
     println!("=== Wikipedia Pageviews to Parquet Converter ===");
     println!("Articles parquet: {}", articles_parquet);
     println!("Output: {}", output_file);
-    println!("Chunk size: {}\n", chunk_size);
+    println!("Chunk size: {}", chunk_size);
+    println!("Bloom filter on qid: {}", bloom_filter);
+    println!(
+        "Input: {}\n",
+        if input_paths.is_empty() {
+            "stdin".to_string()
+        } else {
+            input_paths.join(", ")
+        }
+    );
 
     // Load articles.parquet to get valid article IDs and their QIDs
     let articles_parquet_path: PlPath = PlPath::Local(Arc::from(Path::new(&articles_parquet)));
@@ -59,11 +109,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         article_qid_to_qid.keys().len()
     );
 
-    convert_pageviews_to_parquet(output_file, chunk_size, article_qid_to_qid)?;
+    convert_pageviews_to_parquet(
+        output_file,
+        chunk_size,
+        article_qid_to_qid,
+        bloom_filter,
+        compression,
+        &input_paths,
+    )?;
 
     Ok(())
 }
 
+/// Opens `path` as a plain or gzip-compressed line reader, auto-detecting
+/// gzip by its magic bytes (`1f 8b`) rather than trusting the `.gz`
+/// extension alone, since Wikimedia dump mirrors aren't always consistent
+/// about naming.
+fn open_dump_reader(path: &str) -> Result<Box<dyn BufRead>, Box<dyn std::error::Error>> {
+    let mut file = File::open(path)?;
+    let mut magic = [0u8; 2];
+    let is_gzip = file.read_exact(&mut magic).is_ok() && magic == [0x1f, 0x8b];
+    file.seek(SeekFrom::Start(0))?;
+
+    if is_gzip {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
 fn parse_line(
     line: &str,
     id_to_qid_map: &DirectMap,
@@ -117,95 +191,121 @@ fn process_chunk(records: Vec<PageView>) -> Result<DataFrame, PolarsError> {
     ])
 }
 
+/// Converts line-delimited pageview dump records into a parquet file.
+///
+/// Chunks are processed and written to the output file as soon as they're
+/// ready, one row-group batch at a time, instead of collecting every
+/// chunk's `DataFrame` in memory and concatenating them before a single
+/// final write - so peak memory stays proportional to `chunk_size`, not to
+/// the whole input. The tradeoff: `read_pageviews_for_qids`'s row-group
+/// pruning now only sees each chunk sorted by qid individually (not the
+/// whole file), so its `[min, max]` ranges are looser than a fully
+/// pre-sorted file's - the bloom filter option exists precisely to make up
+/// for that when chunk boundaries don't align with query patterns.
 fn convert_pageviews_to_parquet(
     output_path: &str,
     chunk_size: usize,
     id_to_qid_map: DirectMap,
+    bloom_filter: bool,
+    compression: ParquetCompression,
+    input_paths: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Starting conversion...");
 
-    let stdin = std::io::stdin();
-    let reader = BufReader::new(stdin.lock());
+    let mut readers: Vec<Box<dyn BufRead>> = if input_paths.is_empty() {
+        let stdin = std::io::stdin();
+        vec![Box::new(BufReader::new(stdin))]
+    } else {
+        input_paths
+            .iter()
+            .map(|path| open_dump_reader(path))
+            .collect::<Result<_, _>>()?
+    };
+
+    let schema = Schema::from_iter([
+        Field::new("wiki".into(), DataType::String),
+        Field::new("qid".into(), DataType::UInt32),
+        Field::new("access_method".into(), DataType::Int8),
+        Field::new("daily_views".into(), DataType::UInt32),
+    ]);
+
+    let mut file = File::create(output_path)?;
+    let mut writer = ParquetWriter::new(&mut file)
+        .with_compression(compression)
+        .with_row_group_size(Some(PARQUET_ROW_GROUP_SIZE))
+        .with_dictionary_columns(vec!["wiki".to_string(), "access_method".to_string()]);
+    if bloom_filter {
+        // Split-block bloom filter on "qid" speeds up point-membership reads
+        // (see read_pageviews_for_qids) on files too scattered for the
+        // [min, max] row-group range stat alone to prune well.
+        writer = writer.with_bloom_filter_columns(vec!["qid".to_string()]);
+    }
+    let mut batched_writer = writer.batched(&schema)?;
 
-    let mut chunks = Vec::new();
-    let mut current_chunk = Vec::with_capacity(chunk_size);
     let mut lines_processed = 0;
     let mut valid_records = 0;
+    let mut chunks_written = 0;
     let bytes_read = Arc::new(AtomicUsize::new(0));
+    let mut current_chunk = Vec::with_capacity(chunk_size);
 
-    println!("Reading and chunking data...");
-
-    for line in reader.lines() {
-        let line = line?;
-        let line_bytes = line.len() + 1; // +1 for newline
-        bytes_read.fetch_add(line_bytes, Ordering::Relaxed);
-        lines_processed += 1;
-
-        match parse_line(&line, &id_to_qid_map) {
-            Ok(Some(record)) => {
-                current_chunk.push(record);
-                valid_records += 1;
-
-                if current_chunk.len() >= chunk_size {
-                    chunks.push(current_chunk);
-                    current_chunk = Vec::with_capacity(chunk_size);
+    println!("Streaming chunks of up to {} records...", chunk_size);
+
+    let mut write_chunk =
+        |chunk: Vec<PageView>, batched_writer: &mut BatchedWriter<&mut File>| -> Result<(), Box<dyn std::error::Error>> {
+            let df = process_chunk(chunk)?;
+            // Sort within the chunk so its row groups still get a tighter
+            // qid range than an unsorted chunk would - see the function
+            // doc comment for why this can't be a global sort here.
+            let df = df.sort(["qid"], SortMultipleOptions::default())?;
+            batched_writer.write_batch(&df)?;
+            Ok(())
+        };
+
+    for reader in readers.iter_mut() {
+        for line in reader.lines() {
+            let line = line?;
+            let line_bytes = line.len() + 1; // +1 for newline
+            bytes_read.fetch_add(line_bytes, Ordering::Relaxed);
+            lines_processed += 1;
+
+            match parse_line(&line, &id_to_qid_map) {
+                Ok(Some(record)) => {
+                    current_chunk.push(record);
+                    valid_records += 1;
+
+                    if current_chunk.len() >= chunk_size {
+                        let chunk = std::mem::replace(&mut current_chunk, Vec::with_capacity(chunk_size));
+                        write_chunk(chunk, &mut batched_writer)?;
+                        chunks_written += 1;
+                    }
+                }
+                Ok(None) => {
+                    // Skip records not in main namespace (silently)
+                    continue;
+                }
+                Err(_) => {
+                    // Silently skip malformed lines in production
+                    continue;
                 }
-            }
-            Ok(None) => {
-                // Skip records not in main namespace (silently)
-                continue;
-            }
-            Err(_) => {
-                // Silently skip malformed lines in production
-                continue;
             }
         }
     }
 
-    // Add remaining records
     if !current_chunk.is_empty() {
-        chunks.push(current_chunk);
+        write_chunk(current_chunk, &mut batched_writer)?;
+        chunks_written += 1;
     }
 
-    if chunks.is_empty() {
+    if chunks_written == 0 {
         return Err("No valid data to write".into());
     }
 
-    println!(
-        "\nProcessed {} lines, {} valid records in {} chunks",
-        lines_processed,
-        valid_records,
-        chunks.len()
-    );
-    println!("Processing {} chunks in parallel...", chunks.len());
-
-    // Process chunks in parallel
-    let dataframes: Vec<DataFrame> = chunks
-        .into_par_iter()
-        .filter_map(|chunk| {
-            let result = process_chunk(chunk);
-            result.ok()
-        })
-        .collect();
+    batched_writer.finish()?;
 
-    if dataframes.is_empty() {
-        return Err("No valid dataframes created".into());
-    }
-
-    println!("\nCombining {} dataframes...", dataframes.len());
-    // Convert DataFrame to LazyFrame
-    let lazy_frames: Vec<LazyFrame> = dataframes.into_iter().map(|df| df.lazy()).collect();
-
-    let combined = concat(&lazy_frames, UnionArgs::default())?;
-    println!("Writing to parquet file {} ", &output_path);
-    let mut file = File::create(output_path)?;
-    let mut dataframe = combined.collect()?; // Collect LazyFrame into DataFrame
-    ParquetWriter::new(&mut file)
-        .with_compression(ParquetCompression::Snappy)
-        .finish(&mut dataframe)?; // Pass the DataFrame
     println!("\n✓ Conversion complete!");
     println!("  Lines processed: {}", lines_processed);
     println!("  Valid records: {}", valid_records);
+    println!("  Row-group batches written: {}", chunks_written);
 
     Ok(())
 }
@@ -1,7 +1,13 @@
 use clap::{Arg, Command};
+use parquet::file::page_index::index::Index;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::serialized_reader::ReadOptionsBuilder;
+use parquet::file::statistics::Statistics;
+use parquet::record::RowAccessor;
 use polars::frame::DataFrame;
 use polars::prelude::*;
 use std::{
+    collections::{BinaryHeap, HashMap},
     error::Error,
     fs::File,
     io::{BufWriter, Write},
@@ -116,6 +122,324 @@ pub fn get_daily_pageviews(wiki: &str, year: &i16, month: &i8, day: &i8) -> Vec<
     dense_vector
 }
 
+/// Like [`get_daily_pageviews`], but consults each row group's page-level
+/// column index for the `wiki` column instead of decoding every page
+/// through polars' lazy `filter(col("wiki").eq(...))`. A page whose
+/// `[min, max]` string range can't contain `wiki` is skipped outright; the
+/// rest are still decoded and filtered row by row, same as today. Row
+/// groups with no page index - the file predates page-level statistics, or
+/// was never written with them - fall back to scanning every row, so this
+/// never returns a different result than `get_daily_pageviews`, only reads
+/// less to get there.
+pub fn get_daily_pageviews_paged(wiki: &str, year: &i16, month: &i8, day: &i8) -> Vec<u32> {
+    let graph_builder = GraphBuilder::new(wiki);
+    let graph: WikiGraph = graph_builder.build().expect("Error while building graph");
+
+    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let full_pageviews_file_path = format!(
+        "{}/pageviews/{}/{:02}/{:02}.parquet",
+        data_dir, year, month, day
+    );
+    let articles_parquet_path = format!("{}/{}/articles.parquet", data_dir, wiki);
+
+    if !std::path::Path::new(&full_pageviews_file_path).exists() {
+        eprintln!("Pageview file not found: {}", full_pageviews_file_path);
+        return Vec::new();
+    }
+
+    let articles_parquet: PlPath = PlPath::Local(Arc::from(Path::new(&articles_parquet_path)));
+    let articles_df = LazyFrame::scan_parquet(articles_parquet, Default::default())
+        .expect("Failed to read articles Parquet file")
+        .collect()
+        .expect("Failed to collect articles DataFrame");
+    let article_ids = articles_df
+        .column("page_id")
+        .expect("Missing column: page_id")
+        .u32()
+        .unwrap();
+    let article_qids = articles_df
+        .column("qid")
+        .expect("Missing column: qid")
+        .u32()
+        .unwrap();
+    let article_id_to_qid: DirectMap = article_ids
+        .into_iter()
+        .zip(article_qids.into_iter())
+        .filter_map(|(id, qid)| Some((id?, qid?)))
+        .collect();
+
+    let file =
+        File::open(&full_pageviews_file_path).expect("Failed to open pageviews Parquet file");
+    let options = ReadOptionsBuilder::new().with_page_index(true).build();
+    let reader =
+        SerializedFileReader::new_with_options(file, options).expect("Failed to open Parquet reader");
+    let schema = reader.metadata().file_metadata().schema_descr();
+    let wiki_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "wiki")
+        .expect("wiki column missing from schema");
+    let page_id_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "page_id")
+        .expect("page_id column missing from schema");
+    let views_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "daily_views")
+        .expect("daily_views column missing from schema");
+
+    let mut totals: HashMap<u32, u64> = HashMap::new();
+
+    for rg_idx in 0..reader.metadata().num_row_groups() {
+        let num_rows = reader.metadata().row_group(rg_idx).num_rows() as usize;
+        let row_ranges = wiki_page_row_ranges(&reader, rg_idx, wiki_col, wiki, num_rows);
+
+        let row_group_reader = reader
+            .get_row_group(rg_idx)
+            .expect("Failed to read row group");
+        let mut row_idx = 0usize;
+        for row in row_group_reader
+            .get_row_iter(None)
+            .expect("Failed to iterate rows")
+        {
+            let in_range = match &row_ranges {
+                None => true,
+                Some(ranges) => ranges
+                    .iter()
+                    .any(|&(start, end)| row_idx >= start && row_idx < end),
+            };
+            row_idx += 1;
+            if !in_range {
+                continue; // the page index proved this row's page can't hold `wiki`
+            }
+
+            let row = row.expect("Failed to read row");
+            let row_wiki = row.get_string(wiki_col).expect("Missing wiki value");
+            if row_wiki.as_str() != wiki {
+                continue;
+            }
+            let page_id = row.get_uint(page_id_col).expect("Missing page_id value") as u32;
+            let views = row.get_uint(views_col).expect("Missing daily_views value") as u64;
+            *totals.entry(page_id).or_insert(0) += views;
+        }
+    }
+
+    let mut dense_vector = vec![0u32; graph.art_dense_to_original.len()];
+    for (page_id, views) in totals {
+        if let Some(qid) = article_id_to_qid.get(page_id)
+            && let Some(dense_id) = graph.art_original_to_dense.get(qid)
+        {
+            dense_vector[dense_id as usize] = views as u32;
+        }
+    }
+    dense_vector
+}
+
+/// Row-index ranges within row group `rg_idx` whose page-level `[min, max]`
+/// string range for column `col_idx` could contain `wiki`, derived from the
+/// column index (per-page bounds) and offset index (per-page row offsets).
+/// `None` means either index is missing for this row group/column - the
+/// caller must fall back to scanning every row rather than assuming a page
+/// can be skipped.
+fn wiki_page_row_ranges(
+    reader: &SerializedFileReader<File>,
+    rg_idx: usize,
+    col_idx: usize,
+    wiki: &str,
+    num_rows: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let column_index = reader.metadata().column_index()?;
+    let offset_index = reader.metadata().offset_index()?;
+    let Index::BYTE_ARRAY(native_index) = column_index.get(rg_idx)?.get(col_idx)? else {
+        return None;
+    };
+    let page_locations = &offset_index.get(rg_idx)?.get(col_idx)?.page_locations;
+
+    let page_row_range = |page_idx: usize| -> (usize, usize) {
+        let start = page_locations[page_idx].first_row_index as usize;
+        let end = page_locations
+            .get(page_idx + 1)
+            .map(|p| p.first_row_index as usize)
+            .unwrap_or(num_rows);
+        (start, end)
+    };
+
+    let mut ranges = Vec::new();
+    for (page_idx, page) in native_index.indexes.iter().enumerate() {
+        let (Some(min), Some(max)) = (page.min.as_ref(), page.max.as_ref()) else {
+            // No bounds recorded for this page - it can't be ruled out.
+            ranges.push(page_row_range(page_idx));
+            continue;
+        };
+        let (Ok(min), Ok(max)) = (
+            std::str::from_utf8(min.as_bytes()),
+            std::str::from_utf8(max.as_bytes()),
+        ) else {
+            ranges.push(page_row_range(page_idx));
+            continue;
+        };
+        if wiki < min || wiki > max {
+            continue; // this page's wiki range can't contain `wiki`
+        }
+        ranges.push(page_row_range(page_idx));
+    }
+    Some(ranges)
+}
+
+/// A `(qid, views)` candidate kept in [`get_top_pageviews`]'s bounded
+/// min-heap, ordered so `BinaryHeap::peek`/`pop` surface the *worst* kept
+/// entry (lowest views, then highest qid) - the one to evict when a better
+/// candidate shows up and the heap is already at size `k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PageTotal {
+    views: u64,
+    qid: u32,
+}
+
+impl Ord for PageTotal {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.views.cmp(&self.views).then_with(|| self.qid.cmp(&other.qid))
+    }
+}
+
+impl PartialOrd for PageTotal {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The `daily_views` column's max statistic for one row group, or `u64::MAX`
+/// if the row group carries no statistics - forcing it to always be read
+/// rather than silently skipped.
+fn row_group_max_views(
+    reader: &SerializedFileReader<File>,
+    row_group_idx: usize,
+    views_col: usize,
+) -> u64 {
+    reader
+        .metadata()
+        .row_group(row_group_idx)
+        .column(views_col)
+        .statistics()
+        .and_then(|stats| match stats {
+            Statistics::Int32(s) => s.max_opt().map(|&v| v as u64),
+            Statistics::Int64(s) => s.max_opt().map(|&v| v as u64),
+            _ => None,
+        })
+        .unwrap_or(u64::MAX)
+}
+
+/// Returns `wiki`'s `k` most-viewed articles on the given day as `(qid,
+/// views)` pairs, descending, without a full `group_by` aggregation over
+/// every row like [`get_daily_pageviews`] does. Row groups are visited in
+/// descending order of their `daily_views` max statistic, read straight from
+/// the Parquet footer, and the scan stops as soon as that max can no longer
+/// unseat the current k-th largest total - at that point every row left
+/// unread, including any for `wiki`, is too small to matter.
+pub fn get_top_pageviews(wiki: &str, year: &i16, month: &i8, day: &i8, k: usize) -> Vec<(u32, u64)> {
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let full_pageviews_file_path = format!(
+        "{}/pageviews/{}/{:02}/{:02}.parquet",
+        data_dir, year, month, day
+    );
+    let articles_parquet_path = format!("{}/{}/articles.parquet", data_dir, wiki);
+
+    if !std::path::Path::new(&full_pageviews_file_path).exists() {
+        eprintln!("Pageview file not found: {}", full_pageviews_file_path);
+        return Vec::new();
+    }
+
+    let articles_parquet: PlPath = PlPath::Local(Arc::from(Path::new(&articles_parquet_path)));
+    let articles_df = LazyFrame::scan_parquet(articles_parquet, Default::default())
+        .expect("Failed to read articles Parquet file")
+        .collect()
+        .expect("Failed to collect articles DataFrame");
+    let article_ids = articles_df.column("page_id").expect("Missing column: page_id").u32().unwrap();
+    let article_qids = articles_df.column("qid").expect("Missing column: qid").u32().unwrap();
+    let article_id_to_qid: DirectMap = article_ids
+        .into_iter()
+        .zip(article_qids.into_iter())
+        .filter_map(|(id, qid)| Some((id?, qid?)))
+        .collect();
+
+    let file = File::open(&full_pageviews_file_path).expect("Failed to open pageviews Parquet file");
+    let reader = SerializedFileReader::new(file).expect("Failed to open Parquet reader");
+    let schema = reader.metadata().file_metadata().schema_descr();
+    let wiki_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "wiki")
+        .expect("wiki column missing from schema");
+    let page_id_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "page_id")
+        .expect("page_id column missing from schema");
+    let views_col = schema
+        .columns()
+        .iter()
+        .position(|c| c.name() == "daily_views")
+        .expect("daily_views column missing from schema");
+
+    let mut row_group_order: Vec<usize> = (0..reader.metadata().num_row_groups()).collect();
+    row_group_order.sort_by_key(|&i| std::cmp::Reverse(row_group_max_views(&reader, i, views_col)));
+
+    let mut totals: HashMap<u32, u64> = HashMap::new();
+    let mut heap: BinaryHeap<PageTotal> = BinaryHeap::new();
+
+    for rg_idx in row_group_order {
+        let bound = row_group_max_views(&reader, rg_idx, views_col);
+        if heap.len() >= k {
+            if let Some(worst) = heap.peek() {
+                if worst.views >= bound {
+                    break; // remaining row groups can't unseat the current top-k
+                }
+            }
+        }
+
+        let row_group_reader = reader.get_row_group(rg_idx).expect("Failed to read row group");
+        for row in row_group_reader.get_row_iter(None).expect("Failed to iterate rows") {
+            let row = row.expect("Failed to read row");
+            let row_wiki = row.get_string(wiki_col).expect("Missing wiki value");
+            if row_wiki.as_str() != wiki {
+                continue;
+            }
+            let page_id = row.get_uint(page_id_col).expect("Missing page_id value") as u32;
+            let views = row.get_uint(views_col).expect("Missing daily_views value") as u64;
+            let Some(qid) = article_id_to_qid.get(page_id) else {
+                continue;
+            };
+
+            let total = totals.entry(qid).or_insert(0);
+            *total += views;
+            let entry = PageTotal { views: *total, qid };
+
+            if heap.iter().any(|e| e.qid == qid) {
+                heap.retain(|e| e.qid != qid);
+                heap.push(entry);
+            } else if heap.len() < k {
+                heap.push(entry);
+            } else if let Some(worst) = heap.peek() {
+                if entry.views > worst.views || (entry.views == worst.views && entry.qid < worst.qid) {
+                    heap.pop();
+                    heap.push(entry);
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<(u32, u64)> = heap.into_iter().map(|e| (e.qid, e.views)).collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    result.truncate(k);
+    result
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = Command::new("Per Day Wiki Stats")
         .about("Generates per-day wiki statistics")
@@ -159,6 +483,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .required(true)
                 .value_parser(clap::value_parser!(String)),
         )
+        .arg(
+            Arg::new("mode")
+                .long("mode")
+                .help("How to scan the day's pageviews file before dumping")
+                .value_parser(["full", "paged"])
+                .default_value("full"),
+        )
+        .arg(
+            Arg::new("top-k")
+                .long("top-k")
+                .help(
+                    "Instead of dumping the full dense vector, print the k most-viewed \
+                     articles via row-group-pruned top-K extraction and exit",
+                )
+                .value_parser(clap::value_parser!(usize)),
+        )
         .get_matches();
 
     let wiki = matches.get_one::<String>("wiki").unwrap();
@@ -171,6 +511,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "Processing stats for wiki: {}, date: {}-{}-{}",
         wiki, year, month, day
     );
-    let page_views_dense_vector = get_daily_pageviews(wiki, &{ *year }, &{ *month }, &{ *day });
+
+    if let Some(&k) = matches.get_one::<usize>("top-k") {
+        let top = get_top_pageviews(wiki, year, month, day, k);
+        println!("Top {} articles by views:", top.len());
+        for (qid, views) in top {
+            println!(" - {}: {} views", qid, views);
+        }
+        return Ok(());
+    }
+
+    let page_views_dense_vector = match matches.get_one::<String>("mode").map(String::as_str) {
+        Some("paged") => get_daily_pageviews_paged(wiki, &{ *year }, &{ *month }, &{ *day }),
+        _ => get_daily_pageviews(wiki, &{ *year }, &{ *month }, &{ *day }),
+    };
     generate_bin_dump(page_views_dense_vector, output_path)
 }
@@ -1,13 +1,58 @@
+use memmap2::Mmap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::mem::size_of;
+
+const MAGIC: &[u8; 4] = b"CSR1";
+const VERSION: u32 = 1;
+/// Magic (4) + version (4) + num_nodes (8) + targets len (8).
+const HEADER_LEN: usize = 24;
+
+#[derive(Debug)]
+pub enum CsrAdjacencyError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+    Misaligned,
+}
+
+impl fmt::Display for CsrAdjacencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsrAdjacencyError::InvalidMagic => write!(f, "invalid CSR adjacency file magic"),
+            CsrAdjacencyError::UnsupportedVersion(version) => {
+                write!(f, "unsupported CSR adjacency file version: {}", version)
+            }
+            CsrAdjacencyError::Truncated => {
+                write!(f, "CSR adjacency file truncated before declared length")
+            }
+            CsrAdjacencyError::Misaligned => {
+                write!(f, "CSR adjacency file is not aligned for in-place reinterpretation")
+            }
+        }
+    }
+}
+
+impl Error for CsrAdjacencyError {}
+
+enum Storage {
+    Owned {
+        offsets: Vec<usize>,
+        targets: Vec<u32>,
+    },
+    Mapped {
+        mmap: Mmap,
+        num_nodes: usize,
+        targets_len: usize,
+    },
+}
+
 /// A reusable Compressed Sparse Row (CSR) Adjacency List.
 /// Replaces Vec<Vec<u32>>.
-#[derive(Debug)]
 pub struct CsrAdjacency {
-    // Points to the start index in `targets` for a given ID.
-    // Length = num_nodes + 1
-    offsets: Vec<usize>,
-
-    // The contiguous list of all edges (children/parents).
-    targets: Vec<u32>,
+    storage: Storage,
 }
 
 impl CsrAdjacency {
@@ -15,18 +60,56 @@ impl CsrAdjacency {
     /// Returns an empty slice if the ID is out of bounds.
     #[inline(always)]
     pub fn get(&self, id: u32) -> &[u32] {
+        let (offsets, targets) = self.slices();
         // Safety: We use `get` to avoid panics if ID is bad,
         // though in your optimized graph ID should always be valid.
-        if let Some(&start) = self.offsets.get(id as usize) {
+        if let Some(&start) = offsets.get(id as usize) {
             // We can safely unwrap the end because offsets len is nodes + 1
-            let end = self.offsets[id as usize + 1];
+            let end = offsets[id as usize + 1];
             // Return the slice from the giant targets array
-            &self.targets[start..end]
+            &targets[start..end]
         } else {
             &[]
         }
     }
 
+    /// Total number of edges stored (length of the flattened targets array).
+    pub fn edge_count(&self) -> usize {
+        self.slices().1.len()
+    }
+
+    /// Approximate heap size of the offsets/targets arrays, for engine cache
+    /// memory-budget accounting.
+    pub fn memory_bytes(&self) -> usize {
+        let (offsets, targets) = self.slices();
+        offsets.len() * size_of::<usize>() + targets.len() * size_of::<u32>()
+    }
+
+    /// Returns the `(offsets, targets)` slices backing this structure,
+    /// reinterpreting the mapped bytes directly when loaded from disk
+    /// instead of copying them into owned vectors.
+    fn slices(&self) -> (&[usize], &[u32]) {
+        match &self.storage {
+            Storage::Owned { offsets, targets } => (offsets, targets),
+            Storage::Mapped {
+                mmap,
+                num_nodes,
+                targets_len,
+            } => {
+                let offsets_len = (num_nodes + 1) * size_of::<usize>();
+                let offsets_bytes = &mmap[HEADER_LEN..HEADER_LEN + offsets_len];
+                let targets_bytes = &mmap[HEADER_LEN + offsets_len
+                    ..HEADER_LEN + offsets_len + targets_len * size_of::<u32>()];
+
+                // Safety: `load` already validated that these regions are
+                // aligned and sized for `usize`/`u32` reinterpretation.
+                let (_, offsets, _) = unsafe { offsets_bytes.align_to::<usize>() };
+                let (_, targets, _) = unsafe { targets_bytes.align_to::<u32>() };
+                (offsets, targets)
+            }
+        }
+    }
+
     /// Optimized Builder: Constructs CSR from unsorted pairs (source -> dest).
     /// This uses a "Bucket Sort" approach (2-pass) to avoid resizing vectors.
     ///
@@ -69,7 +152,79 @@ impl CsrAdjacency {
             }
         }
 
-        CsrAdjacency { offsets, targets }
+        CsrAdjacency {
+            storage: Storage::Owned { offsets, targets },
+        }
+    }
+
+    /// Serializes this structure to `path` as a self-describing binary
+    /// blob, mirroring how the pageview dump is handled on disk: a `CSR1`
+    /// magic, version, `num_nodes` and `targets` length, then the raw
+    /// `offsets`/`targets` arrays written native-endian.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let (offsets, targets) = self.slices();
+        let num_nodes = offsets.len() - 1;
+
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_ne_bytes())?;
+        file.write_all(&(num_nodes as u64).to_ne_bytes())?;
+        file.write_all(&(targets.len() as u64).to_ne_bytes())?;
+
+        // Safety: `offsets`/`targets` are plain-old-data `usize`/`u32`
+        // vectors; we only read their bytes for the duration of the write.
+        let offsets_bytes = unsafe {
+            std::slice::from_raw_parts(offsets.as_ptr() as *const u8, std::mem::size_of_val(offsets))
+        };
+        let targets_bytes = unsafe {
+            std::slice::from_raw_parts(targets.as_ptr() as *const u8, std::mem::size_of_val(targets))
+        };
+        file.write_all(offsets_bytes)?;
+        file.write_all(targets_bytes)?;
+
+        Ok(())
+    }
+
+    /// Memory-maps `path` and reinterprets the mapped bytes directly as the
+    /// `offsets`/`targets` arrays, validating the header before trusting
+    /// them. Construction is O(1): no degree counting or copying.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let file = File::open(path)?;
+        // Safety: the file is treated as read-only for the lifetime of the
+        // mapping; we don't guard against concurrent external writers.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        if mmap.len() < HEADER_LEN || &mmap[0..4] != MAGIC {
+            return Err(Box::new(CsrAdjacencyError::InvalidMagic));
+        }
+
+        let version = u32::from_ne_bytes(mmap[4..8].try_into().unwrap());
+        if version != VERSION {
+            return Err(Box::new(CsrAdjacencyError::UnsupportedVersion(version)));
+        }
+
+        let num_nodes = u64::from_ne_bytes(mmap[8..16].try_into().unwrap()) as usize;
+        let targets_len = u64::from_ne_bytes(mmap[16..24].try_into().unwrap()) as usize;
+
+        let offsets_len = (num_nodes + 1) * size_of::<usize>();
+        let expected_len = HEADER_LEN + offsets_len + targets_len * size_of::<u32>();
+        if mmap.len() < expected_len {
+            return Err(Box::new(CsrAdjacencyError::Truncated));
+        }
+
+        let offsets_bytes = &mmap[HEADER_LEN..HEADER_LEN + offsets_len];
+        let (head, _, tail) = unsafe { offsets_bytes.align_to::<usize>() };
+        if !head.is_empty() || !tail.is_empty() {
+            return Err(Box::new(CsrAdjacencyError::Misaligned));
+        }
+
+        Ok(Self {
+            storage: Storage::Mapped {
+                mmap,
+                num_nodes,
+                targets_len,
+            },
+        })
     }
 }
 
@@ -150,4 +305,25 @@ mod tests {
         assert_eq!(csr.get(0), &[100]);
         assert_eq!(csr.get(1), &[200]);
     }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let pairs = vec![(0, 10), (1, 20), (2, 30), (1, 21)];
+        let csr = CsrAdjacency::from_pairs(3, &pairs);
+
+        let path = std::env::temp_dir().join(format!(
+            "csr_adjacency_test_{}.bin",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+
+        csr.save(path).expect("save should succeed");
+        let loaded = CsrAdjacency::load(path).expect("load should succeed");
+
+        assert_eq!(loaded.get(0), csr.get(0));
+        assert_eq!(loaded.get(1), csr.get(1));
+        assert_eq!(loaded.get(2), csr.get(2));
+
+        std::fs::remove_file(path).ok();
+    }
 }
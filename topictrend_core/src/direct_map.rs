@@ -51,6 +51,12 @@ impl DirectMap {
             _ => None,
         }
     }
+    /// Approximate heap size of the backing vector, for engine cache
+    /// memory-budget accounting.
+    pub fn memory_bytes(&self) -> usize {
+        self.mapping.len() * std::mem::size_of::<u32>()
+    }
+
     /// Returns a vector of all keys that have an associated value.
     ///
     /// # Returns
@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+
+const MAGIC: &[u8; 4] = b"VIEW";
+const SUPPORTED_VERSION: u32 = 1;
+/// Magic (4) + Version (4) + Size (8).
+const HEADER_LEN: usize = 16;
+
+#[derive(Debug)]
+pub enum PageViewsError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    Truncated,
+}
+
+impl fmt::Display for PageViewsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PageViewsError::InvalidMagic => write!(f, "invalid pageview file magic"),
+            PageViewsError::UnsupportedVersion(version) => {
+                write!(f, "unsupported pageview file version: {}", version)
+            }
+            PageViewsError::Truncated => write!(f, "pageview file truncated before declared size"),
+        }
+    }
+}
+
+impl Error for PageViewsError {}
+
+/// Per-dense-id view counts loaded from a `generate_bin_dump` file: a
+/// `VIEW` magic, a version and a declared element count, followed by the
+/// raw `u32` array.
+#[derive(Debug)]
+pub struct PageViews {
+    views: Vec<u32>,
+}
+
+impl PageViews {
+    /// Reads and validates a `VIEW`-format dump, checking the magic and
+    /// version before trusting the declared size.
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut file = File::open(path)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+
+        if buffer.len() < HEADER_LEN || &buffer[0..4] != MAGIC {
+            return Err(Box::new(PageViewsError::InvalidMagic));
+        }
+
+        let version = u32::from_le_bytes(buffer[4..8].try_into().unwrap());
+        if version != SUPPORTED_VERSION {
+            return Err(Box::new(PageViewsError::UnsupportedVersion(version)));
+        }
+
+        let size = u64::from_le_bytes(buffer[8..16].try_into().unwrap()) as usize;
+        let (_head, body, _tail) = unsafe { buffer[HEADER_LEN..].align_to::<u32>() };
+        if body.len() < size {
+            return Err(Box::new(PageViewsError::Truncated));
+        }
+
+        Ok(Self {
+            views: body[..size].to_vec(),
+        })
+    }
+
+    /// View count for a dense id, or 0 for an id with no recorded views
+    /// (including ids past the end of the file).
+    pub fn get(&self, dense_id: u32) -> u32 {
+        self.views.get(dense_id as usize).copied().unwrap_or(0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+}
@@ -1,10 +1,93 @@
 use crate::{graphbuilder::GraphBuilder, wikigraph::WikiGraph};
 use chrono::{Datelike, NaiveDate};
+use memmap2::Mmap;
 use roaring::RoaringBitmap;
+use rusqlite::OptionalExtension;
+use serde::Serialize;
 use std::fmt;
-use std::io::Read;
+use std::io::{BufWriter, Write as IoWrite};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
 use std::time::{Duration, Instant};
-use std::{collections::HashMap, error::Error, fs::File};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs::File,
+};
+
+/// One raw event from the self-profiler: a query's start/end, a named
+/// phase boundary within a query (aggregation/scatter/sort), or a cache
+/// hit/miss. Appended as one JSONL record each so totals and hit ratios
+/// are computed offline instead of on the hot path - see [`Profiler`].
+#[derive(Debug, Serialize)]
+struct ProfileEvent<'a> {
+    ts_nanos: u128,
+    thread_id: String,
+    query: &'a str,
+    phase: &'a str,
+    key: &'a str,
+    elapsed_nanos: Option<u128>,
+}
+
+/// Opt-in raw event recorder for `PageViewEngine` queries, enabled by
+/// setting `TOPICTREND_PROFILE_LOG` to an output path. When unset, this
+/// is a no-op recorder so the hot path pays nothing beyond one `Option`
+/// check per event. Mirrors the "record the raw events, analyze later"
+/// profiler design: a cheap append-only recorder here, with separate
+/// offline tooling expected to compute per-query totals and cache-hit
+/// ratios from the JSONL it writes.
+#[derive(Debug)]
+struct Profiler {
+    writer: Option<Mutex<BufWriter<File>>>,
+}
+
+impl Profiler {
+    fn from_env() -> Self {
+        let writer = std::env::var("TOPICTREND_PROFILE_LOG").ok().map(|path| {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .unwrap_or_else(|err| panic!("failed to open profile log {}: {}", path, err));
+            Mutex::new(BufWriter::new(file))
+        });
+        Self { writer }
+    }
+
+    fn record(&self, query: &str, phase: &str, key: &str, elapsed: Option<Duration>) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+
+        let ts_nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let event = ProfileEvent {
+            ts_nanos,
+            thread_id: format!("{:?}", std::thread::current().id()),
+            query,
+            phase,
+            key,
+            elapsed_nanos: elapsed.map(|d| d.as_nanos()),
+        };
+
+        if let Ok(line) = serde_json::to_string(&event) {
+            if let Ok(mut writer) = writer.lock() {
+                let _ = writeln!(writer, "{}", line);
+            }
+        }
+    }
+
+    fn event(&self, query: &str, phase: &str, key: &str) {
+        self.record(query, phase, key, None);
+    }
+
+    fn phase(&self, query: &str, phase: &str, key: &str, elapsed: Duration) {
+        self.record(query, phase, key, Some(elapsed));
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ArticleRank {
@@ -64,14 +147,12 @@ impl TopCategoriesCacheEntry {
 #[derive(Debug)]
 pub struct TopCategoriesCache {
     cache: HashMap<TopCategoriesCacheKey, TopCategoriesCacheEntry>,
-    last_cleanup: Instant,
 }
 
 impl TopCategoriesCache {
     fn new() -> Self {
         Self {
             cache: HashMap::new(),
-            last_cleanup: Instant::now(),
         }
     }
 
@@ -109,67 +190,608 @@ impl TopCategoriesCache {
             ttl,
         };
         self.cache.insert(key, entry);
-
-        // Cleanup expired entries every 10 minutes
-        if self.last_cleanup.elapsed() > Duration::from_secs(10 * 60) {
-            self.cleanup_expired();
-            self.last_cleanup = Instant::now();
-        }
     }
 
-    fn cleanup_expired(&mut self) {
+    /// Drops every entry past its TTL. Called by
+    /// [`TopCategoriesCacheWorker`] on its own schedule rather than as a
+    /// side effect of `insert`, so a cache that stops receiving inserts
+    /// still gets reclaimed.
+    fn evict_expired(&mut self) {
         self.cache.retain(|_, entry| !entry.is_expired());
     }
 
+    /// If the cache holds more than `max_entries`, evicts the
+    /// soonest-to-expire entries first until it's back within budget.
+    fn enforce_budget(&mut self, max_entries: usize) {
+        if self.cache.len() <= max_entries {
+            return;
+        }
+        let mut by_expiry: Vec<(TopCategoriesCacheKey, Instant)> = self
+            .cache
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.created_at + entry.ttl))
+            .collect();
+        by_expiry.sort_by_key(|(_, expires_at)| *expires_at);
+
+        let overflow = self.cache.len() - max_entries;
+        for (key, _) in by_expiry.into_iter().take(overflow) {
+            self.cache.remove(&key);
+        }
+    }
+
     fn clear(&mut self) {
         self.cache.clear();
     }
 }
 
+/// Background lifecycle worker for a [`TopCategoriesCache`]: periodically
+/// walks the cache on its own schedule (independent of request traffic),
+/// dropping expired entries and, if `max_entries` is set, trimming the
+/// soonest-to-expire entries until the cache is back within budget.
+pub struct TopCategoriesCacheWorker {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl TopCategoriesCacheWorker {
+    /// Spawns the scan loop against a shared, `Arc<Mutex<...>>`-wrapped
+    /// cache so the worker thread and request threads coordinate through
+    /// the same lock [`PageViewEngine`] uses.
+    pub fn start(
+        cache: Arc<Mutex<TopCategoriesCache>>,
+        scan_interval: Duration,
+        max_entries: Option<usize>,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_for_thread = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            // Sleep in short slices rather than one long sleep so `stop()`
+            // takes effect promptly instead of waiting out the full
+            // `scan_interval`.
+            const POLL: Duration = Duration::from_millis(200);
+            let mut since_last_scan = Duration::ZERO;
+
+            while !stop_for_thread.load(Ordering::Relaxed) {
+                let slice = POLL.min(scan_interval);
+                thread::sleep(slice);
+                since_last_scan += slice;
+
+                if since_last_scan < scan_interval {
+                    continue;
+                }
+                since_last_scan = Duration::ZERO;
+
+                let mut cache = cache.lock().unwrap();
+                cache.evict_expired();
+                if let Some(max_entries) = max_entries {
+                    cache.enforce_budget(max_entries);
+                }
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Signals the scan loop to exit and blocks until it has.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for TopCategoriesCacheWorker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Persistent state behind [`PageViewEngine::get_top_categories_incremental`].
+/// Holds the current window's per-article view totals and per-category
+/// scores/article lists, so a window shift can apply signed day-deltas
+/// instead of a full recompute. `top_ids` is the previous call's top-N
+/// (dense category ids, descending by score) and is the other half of the
+/// "only touch what changed" re-rank: the next top-N can only contain ids
+/// from this set or from the current dirty set.
 #[derive(Debug)]
+struct IncrementalWindow {
+    start: NaiveDate,
+    end: NaiveDate,
+    article_views: Vec<u32>,
+    cat_scores: Vec<u64>,
+    // Running per-article view total within the window, keyed by dense
+    // article id. A `HashMap` (rather than a flat `Vec`) so a window shift
+    // can add/remove a handful of entries without re-aggregating every
+    // article in the category.
+    cat_articles: Vec<HashMap<u32, u32>>,
+    top_ids: Vec<usize>,
+}
+
 pub struct PageViewEngine {
     // Map Date -> Vector of pageviews (Index is Dense Article ID)
-    // We use Arc to make it cheap to clone/share across web threads
-    daily_views: HashMap<NaiveDate, Vec<u32>>,
+    // Behind a RwLock so trend queries can take &self and run concurrently,
+    // only blocking each other while a day's data is actually being loaded.
+    daily_views: RwLock<HashMap<NaiveDate, DailyViews>>,
     wiki: String,
     wikigraph: WikiGraph,
-    top_categories_cache: TopCategoriesCache,
+    // An `Arc<Mutex<...>>` (rather than the engine's own `RwLock`s) so a
+    // [`TopCategoriesCacheWorker`] can hold a clone of the same handle and
+    // evict from a background thread without going through `&self`.
+    top_categories_cache: Arc<Mutex<TopCategoriesCache>>,
+    profiler: Profiler,
+    incremental_top_categories: Mutex<Option<IncrementalWindow>>,
+    store: Box<dyn PageViewStore>,
+}
+
+impl fmt::Debug for PageViewEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageViewEngine")
+            .field("wiki", &self.wiki)
+            .field("wikigraph", &self.wikigraph)
+            .field("daily_views", &self.daily_views)
+            .field("top_categories_cache", &self.top_categories_cache)
+            .field("profiler", &self.profiler)
+            .field("incremental_top_categories", &self.incremental_top_categories)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns every date in `[from_start, from_end]` that does not also fall
+/// inside `[excl_start, excl_end]`, used by `apply_window_shift` to find the
+/// days a window shift expires/includes.
+fn days_in_range_excluding(
+    from_start: NaiveDate,
+    from_end: NaiveDate,
+    excl_start: NaiveDate,
+    excl_end: NaiveDate,
+) -> Vec<NaiveDate> {
+    let mut days = Vec::new();
+    let mut curr = from_start;
+    while curr <= from_end {
+        if curr < excl_start || curr > excl_end {
+            days.push(curr);
+        }
+        curr = curr.succ_opt().unwrap();
+    }
+    days
+}
+
+/// Header version written by the dense per-article `.bin` dumper (a raw
+/// `u32` per article, most of them zero).
+const DAILY_VIEWS_VERSION_DENSE: u32 = 1;
+/// Header version for the sparse, dictionary-encoded dump: only non-zero
+/// entries as (sorted dense-id, value) pairs, the values optionally
+/// narrowed to indices into a small dictionary of distinct counts.
+const DAILY_VIEWS_VERSION_SPARSE: u32 = 2;
+
+/// A byte source a [`U32Slice`] can reinterpret in place, so the same
+/// `DailyViews` parsing works whether the bytes came from a memory-mapped
+/// `.bin` file ([`FsPageViewStore`]) or an owned blob read out of an
+/// embedded database ([`SqlitePageViewStore`]). Cloning this is an `Arc`
+/// refcount bump, not a copy of the underlying bytes - sharing an
+/// already-loaded day across the web server's threads no longer costs a
+/// ~28 MB clone, and for the mapped variant the OS page cache (not our
+/// heap) manages residency for cold historical days.
+#[derive(Debug, Clone)]
+enum ViewBytes {
+    Mapped(Arc<Mmap>),
+    Owned(Arc<Vec<u8>>),
+}
+
+impl std::ops::Deref for ViewBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            ViewBytes::Mapped(mmap) => mmap,
+            ViewBytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// A `u32` array living inside a [`ViewBytes`] source.
+#[derive(Debug, Clone)]
+struct U32Slice {
+    bytes: ViewBytes,
+    offset: usize,
+    len: usize,
+}
+
+impl U32Slice {
+    fn as_slice(&self) -> &[u32] {
+        let bytes = &self.bytes[self.offset..self.offset + self.len * std::mem::size_of::<u32>()];
+        // Safety: `parse_daily_views` only ever builds this from an offset
+        // that is a multiple of 4 (the header is 16 bytes, and every section
+        // preceding a `u32` array is itself a whole number of `u32`s), and
+        // both a mmap's base address and a `Vec<u8>`'s allocation are at
+        // least 4-byte aligned, so `offset` stays 4-byte aligned relative to
+        // the underlying buffer's base address.
+        let (_head, body, _tail) = unsafe { bytes.align_to::<u32>() };
+        body
+    }
+}
+
+/// One day's per-article view counts, either a dense `[u32; num_articles]`
+/// array or, when most articles got zero views that day, a sparse pair of
+/// parallel arrays holding only the non-zero entries - see `load_bin_file`.
+/// Both variants read from the same [`ViewBytes`] buffer, memory-mapped or
+/// owned; `Sparse`'s
+/// dictionary indirection (if present) is resolved once at load time since
+/// it's a small, fixed amount of work independent of `num_articles`.
+#[derive(Debug, Clone)]
+enum DailyViews {
+    Dense(U32Slice),
+    Sparse { ids: U32Slice, values: Vec<u32> },
+}
+
+impl DailyViews {
+    fn get(&self, article_dense_id: usize) -> Option<u32> {
+        match self {
+            DailyViews::Dense(views) => views.as_slice().get(article_dense_id).copied(),
+            DailyViews::Sparse { ids, values } => ids
+                .as_slice()
+                .binary_search(&(article_dense_id as u32))
+                .ok()
+                .map(|pos| values[pos]),
+        }
+    }
+
+    /// Number of entries actually stored - `num_articles` for `Dense`, the
+    /// count of non-zero articles for `Sparse`.
+    fn len(&self) -> usize {
+        match self {
+            DailyViews::Dense(views) => views.len,
+            DailyViews::Sparse { ids, .. } => ids.len,
+        }
+    }
+
+    fn approx_memory_bytes(&self) -> usize {
+        // The mapped bytes themselves are shared with the OS page cache,
+        // not counted against our own heap budget - only the small owned
+        // `values` dictionary-resolved column (for `Sparse`) is ours.
+        match self {
+            DailyViews::Dense(_) => 0,
+            DailyViews::Sparse { values, .. } => values.len() * std::mem::size_of::<u32>(),
+        }
+    }
+
+    /// Iterates `(dense_article_id, views)` pairs with `views > 0` only, so
+    /// aggregation loops can skip the zero-view majority directly instead of
+    /// visiting every article and checking - for `Sparse` every stored
+    /// entry already satisfies this by construction.
+    fn iter_nonzero(&self) -> Box<dyn Iterator<Item = (usize, u32)> + '_> {
+        match self {
+            DailyViews::Dense(views) => Box::new(
+                views
+                    .as_slice()
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &v)| v != 0)
+                    .map(|(i, &v)| (i, v)),
+            ),
+            DailyViews::Sparse { ids, values } => Box::new(
+                ids.as_slice()
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(&id, &v)| (id as usize, v)),
+            ),
+        }
+    }
 }
 
-pub fn load_bin_file(path: &str, expected_size: usize) -> Result<Vec<u32>, Box<dyn Error>> {
-    let mut file = File::open(path)?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
+/// Parses the sparse body format written after the common 16-byte header:
+/// a `u32` dictionary length, then `count` (read from the header's size
+/// field) sorted `u32` dense ids, then either `count` raw `u32` values
+/// (`dict_len == 0`) or a `dict_len`-entry `u32` dictionary of distinct
+/// view counts followed by `count` `u16` indices into it. The `ids` column
+/// stays a zero-copy mmap slice; `values` is small (one `u32` per non-zero
+/// article) so dictionary indices are resolved once, here, into an owned
+/// column rather than threading the dictionary lookup through every read.
+fn read_sparse_daily_views(bytes: &ViewBytes, expected_size: usize) -> DailyViews {
+    let count = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+    let mut offset = 16;
+
+    let dict_len = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+    offset += 4;
+
+    let ids = U32Slice {
+        bytes: bytes.clone(),
+        offset,
+        len: count,
+    };
+    offset += count * std::mem::size_of::<u32>();
+
+    let values = if dict_len > 0 {
+        let mut dict = Vec::with_capacity(dict_len);
+        for _ in 0..dict_len {
+            dict.push(u32::from_le_bytes(
+                bytes[offset..offset + 4].try_into().unwrap(),
+            ));
+            offset += 4;
+        }
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            let idx = u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap()) as usize;
+            offset += 2;
+            values.push(dict[idx]);
+        }
+        values
+    } else {
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            values.push(u32::from_le_bytes(
+                bytes[offset..offset + 4].try_into().unwrap(),
+            ));
+            offset += 4;
+        }
+        values
+    };
+
+    if let Some(&max_id) = ids.as_slice().last() {
+        if max_id as usize >= expected_size {
+            eprintln!(
+                "Graph/View Mismatch! Re-run the pipeline.Expected < {} Got max id:{}",
+                expected_size, max_id
+            );
+        }
+    }
+
+    DailyViews::Sparse { ids, values }
+}
 
+/// Validates the `VIEW` magic plus the 16-byte header against `bytes`
+/// without copying it, then parses the dense or sparse body depending on
+/// the header's version field. Shared between [`load_bin_file`] (mapped
+/// bytes) and [`SqlitePageViewStore`] (an owned blob read out of a BLOB
+/// column), so both backends get the same zero-copy `Dense` reinterpretation
+/// and the same one-time sparse dictionary resolution.
+fn parse_daily_views(bytes: ViewBytes, expected_size: usize) -> DailyViews {
     // Simple Header Check
-    if &buffer[0..4] != b"VIEW" {
+    if &bytes[0..4] != b"VIEW" {
         panic!("Invalid Magic");
     }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
 
-    // Cast raw bytes to u32 slice (unsafe/fast or using bytemuck)
-    // This skips parsing entirely.
-    let (_head, body, _tail) = unsafe { buffer[16..].align_to::<u32>() };
+    match version {
+        DAILY_VIEWS_VERSION_SPARSE => read_sparse_daily_views(&bytes, expected_size),
+        _ => {
+            debug_assert_eq!(version, DAILY_VIEWS_VERSION_DENSE);
+            let len = (bytes.len() - 16) / std::mem::size_of::<u32>();
 
-    if body.len() != expected_size {
-        eprintln!(
-            "Graph/View Mismatch! Re-run the pipeline.Expected {} Got:{}",
-            expected_size,
-            body.len()
+            if len != expected_size {
+                eprintln!(
+                    "Graph/View Mismatch! Re-run the pipeline.Expected {} Got:{}",
+                    expected_size, len
+                );
+            }
+
+            DailyViews::Dense(U32Slice {
+                bytes,
+                offset: 16,
+                len,
+            })
+        }
+    }
+}
+
+/// Memory-maps `path` and parses it via [`parse_daily_views`] without
+/// copying it; the returned [`DailyViews`] then reinterprets the mapped
+/// bytes in place (`Dense`) or resolves the small sparse dictionary once
+/// (`Sparse`). Sharing an already-loaded day across threads is an `Arc`
+/// clone of the mapping, not a copy of its contents.
+pub fn load_bin_file(path: &str, expected_size: usize) -> Result<DailyViews, Box<dyn Error>> {
+    let file = File::open(path)?;
+    // Safety: the file is treated as read-only for the lifetime of the
+    // mapping; we don't guard against concurrent external writers.
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(parse_daily_views(
+        ViewBytes::Mapped(Arc::new(mmap)),
+        expected_size,
+    ))
+}
+
+/// Backend for loading a wiki's per-day pageview history, independent of
+/// whether it lives as one `.bin` file per day on disk or as rows in an
+/// embedded database. [`PageViewEngine`] only talks to whichever is
+/// configured through this trait.
+pub trait PageViewStore: Send + Sync {
+    fn load_day(&self, wiki: &str, date: NaiveDate) -> Result<Option<DailyViews>, Box<dyn Error>>;
+
+    /// Loads every available day in `[start_date, end_date]`. The default
+    /// implementation calls `load_day` once per day; a backend that can
+    /// batch-fetch a contiguous range in one round trip (e.g. a single SQL
+    /// query spanning the whole range) should override this.
+    fn load_range(
+        &self,
+        wiki: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, DailyViews)>, Box<dyn Error>> {
+        let mut results = Vec::new();
+        let mut curr = start_date;
+        while curr <= end_date {
+            if let Some(day) = self.load_day(wiki, curr)? {
+                results.push((curr, day));
+            }
+            curr = curr.succ_opt().unwrap();
+        }
+        Ok(results)
+    }
+}
+
+/// The original on-disk layout: one `.bin` file per wiki/year/month/day
+/// under `data_dir`, as produced by the CLI's pageview dump pipeline.
+pub struct FsPageViewStore {
+    data_dir: String,
+    num_articles: usize,
+}
+
+impl FsPageViewStore {
+    pub fn new(data_dir: String, num_articles: usize) -> Self {
+        Self {
+            data_dir,
+            num_articles,
+        }
+    }
+}
+
+impl PageViewStore for FsPageViewStore {
+    fn load_day(&self, wiki: &str, date: NaiveDate) -> Result<Option<DailyViews>, Box<dyn Error>> {
+        let bin_filename = format!(
+            "{}/{}/pageviews/{}/{:02}/{:02}.bin",
+            self.data_dir,
+            wiki,
+            date.year(),
+            date.month(),
+            date.day()
+        );
+
+        if !std::path::Path::new(&bin_filename).exists() {
+            return Ok(None);
+        }
+
+        let day_vec = load_bin_file(&bin_filename, self.num_articles)
+            .expect("Error reading the pageview bin file");
+        println!(
+            "Loaded page views for {} on {}, found {} articles",
+            wiki,
+            date,
+            day_vec.len()
         );
+
+        Ok(Some(day_vec))
     }
+}
 
-    Ok(body.to_vec())
+/// An embedded-database-backed store, for deployments that would rather
+/// keep pageview history in one SQLite file than thousands of per-day
+/// `.bin` files. Each row stores the identical bytes `load_bin_file` would
+/// read from disk (header included), so [`parse_daily_views`] is shared
+/// between both backends.
+pub struct SqlitePageViewStore {
+    conn: Mutex<rusqlite::Connection>,
+    num_articles: usize,
+}
+
+impl SqlitePageViewStore {
+    pub fn open(path: &str, num_articles: usize) -> rusqlite::Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS daily_views (
+                wiki TEXT NOT NULL,
+                date TEXT NOT NULL,
+                blob BLOB NOT NULL,
+                PRIMARY KEY (wiki, date)
+            )",
+            [],
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            num_articles,
+        })
+    }
+}
+
+impl PageViewStore for SqlitePageViewStore {
+    fn load_day(&self, wiki: &str, date: NaiveDate) -> Result<Option<DailyViews>, Box<dyn Error>> {
+        let conn = self.conn.lock().unwrap();
+        let blob: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT blob FROM daily_views WHERE wiki = ?1 AND date = ?2",
+                rusqlite::params![wiki, date.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(blob.map(|bytes| {
+            parse_daily_views(ViewBytes::Owned(Arc::new(bytes)), self.num_articles)
+        }))
+    }
+
+    /// Fetches every row in the range with one query/transaction instead of
+    /// one `SELECT` per day.
+    fn load_range(
+        &self,
+        wiki: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, DailyViews)>, Box<dyn Error>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, blob FROM daily_views WHERE wiki = ?1 AND date BETWEEN ?2 AND ?3 ORDER BY date",
+        )?;
+        let rows = stmt.query_map(
+            rusqlite::params![wiki, start_date.to_string(), end_date.to_string()],
+            |row| {
+                let date: String = row.get(0)?;
+                let blob: Vec<u8> = row.get(1)?;
+                Ok((date, blob))
+            },
+        )?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let (date_str, blob) = row?;
+            let date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")?;
+            results.push((
+                date,
+                parse_daily_views(ViewBytes::Owned(Arc::new(blob)), self.num_articles),
+            ));
+        }
+        Ok(results)
+    }
+}
+
+/// Builds the [`PageViewStore`] `PageViewEngine::new` wires in, selected via
+/// `PAGEVIEW_STORE_BACKEND` (`"fs"`, the default, or `"sqlite"`) so a
+/// deployment can point at a [`SqlitePageViewStore`] without a code change.
+/// The sqlite backend's file path comes from `PAGEVIEW_SQLITE_PATH`,
+/// defaulting to `{DATA_DIR}/{wiki}/pageviews.sqlite3`.
+fn build_pageview_store(wiki: &str, data_dir: &str, num_articles: usize) -> Box<dyn PageViewStore> {
+    match std::env::var("PAGEVIEW_STORE_BACKEND").as_deref() {
+        Ok("sqlite") => {
+            let path = std::env::var("PAGEVIEW_SQLITE_PATH")
+                .unwrap_or_else(|_| format!("{}/{}/pageviews.sqlite3", data_dir, wiki));
+            Box::new(
+                SqlitePageViewStore::open(&path, num_articles)
+                    .unwrap_or_else(|e| panic!("Failed to open sqlite pageview store {}: {}", path, e)),
+            )
+        }
+        _ => Box::new(FsPageViewStore::new(data_dir.to_string(), num_articles)),
+    }
 }
 
 impl PageViewEngine {
     pub fn new(wiki: &str) -> Self {
         let graph_builder = GraphBuilder::new(wiki);
         let graph: WikiGraph = graph_builder.build().expect("Error while building graph");
+        let num_articles = graph.art_dense_to_original.len();
+        let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+        let store = build_pageview_store(wiki, &data_dir, num_articles);
+        Self::with_store(wiki, graph, store)
+    }
+
+    /// Like [`PageViewEngine::new`], but with the pageview history backend
+    /// supplied explicitly instead of always defaulting to [`FsPageViewStore`]
+    /// against `DATA_DIR` - e.g. to point a deployment at a
+    /// [`SqlitePageViewStore`] instead.
+    pub fn with_store(wiki: &str, wikigraph: WikiGraph, store: Box<dyn PageViewStore>) -> Self {
         Self {
             wiki: wiki.to_string(),
-            daily_views: HashMap::new(),
-            wikigraph: graph,
-            top_categories_cache: TopCategoriesCache::new(),
+            daily_views: RwLock::new(HashMap::new()),
+            wikigraph,
+            top_categories_cache: Arc::new(Mutex::new(TopCategoriesCache::new())),
+            profiler: Profiler::from_env(),
+            incremental_top_categories: Mutex::new(None),
+            store,
         }
     }
 
@@ -177,200 +799,254 @@ impl PageViewEngine {
         &self.wikigraph
     }
 
+    /// Approximate total heap size of the graph plus any pageview history
+    /// currently cached in memory, used by the engine cache's eviction
+    /// budget.
+    pub fn approx_memory_bytes(&self) -> usize {
+        let daily_views_bytes: usize = self
+            .daily_views
+            .read()
+            .unwrap()
+            .values()
+            .map(|day| day.approx_memory_bytes())
+            .sum();
+
+        self.wikigraph.approx_memory_bytes() + daily_views_bytes
+    }
+
     pub fn get_category_trend(
-        &mut self,
+        &self,
         category_qid: u32,
         depth: u32,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Vec<(NaiveDate, u64)> {
-        let mut results = Vec::new();
-        let article_mask = match self
-            .wikigraph
-            .get_articles_in_category_as_dense(category_qid, depth)
-        {
-            Ok(mask) => mask,
-            Err(err) => {
-                eprintln!("Error: {}", err);
+        let query_start = Instant::now();
+        let key = format!(
+            "category_qid={category_qid} depth={depth} start={start_date} end={end_date}"
+        );
+        self.profiler.event("get_category_trend", "start", &key);
+
+        let results = (|| {
+            let mut results = Vec::new();
+            let article_mask = match self
+                .wikigraph
+                .get_articles_in_category_as_dense(category_qid, depth)
+            {
+                Ok(mask) => mask,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    return vec![];
+                }
+            };
+
+            // Optimization: If mask is empty, return early
+            if article_mask.is_empty() {
+                eprintln!(
+                    "Could not find articles in category: {}/{}",
+                    self.wiki, category_qid
+                );
                 return vec![];
             }
-        };
-
-        // Optimization: If mask is empty, return early
-        if article_mask.is_empty() {
-            eprintln!(
-                "Could not find articles in category: {}/{}",
-                self.wiki, category_qid
+            println!(
+                "Found {} articles in category {}/{} at depth {}",
+                article_mask.len(),
+                self.wiki,
+                &category_qid,
+                depth
             );
-            return vec![];
-        }
-        println!(
-            "Found {} articles in category {}/{} at depth {}",
-            article_mask.len(),
-            self.wiki,
-            &category_qid,
-            depth
-        );
 
-        self.load_history_for_date_range(start_date, end_date)
-            .expect("Error in loading pageview history");
+            self.load_history_for_date_range(start_date, end_date)
+                .expect("Error in loading pageview history");
 
-        let mut curr = start_date;
-        while curr <= end_date {
-            if let Some(day_data) = self.daily_views.get(&curr) {
-                // High Performance Loop
-                // Summing values only for articles in the category
-                let mut daily_total: u64 = 0;
-
-                // RoaringBitmap iter is sorted, which is cache-friendly
-                for article_dense_id in article_mask.iter() {
-                    // distinct get is O(1)
-                    // We use get unchecked for max speed if we are sure indices are valid
-                    if let Some(&views) = day_data.get(article_dense_id as usize) {
-                        daily_total += views as u64;
+            let daily_views = self.daily_views.read().unwrap();
+            let mut curr = start_date;
+            while curr <= end_date {
+                if let Some(day_data) = daily_views.get(&curr) {
+                    // High Performance Loop
+                    // Summing values only for articles in the category
+                    let mut daily_total: u64 = 0;
+
+                    // RoaringBitmap iter is sorted, which is cache-friendly
+                    for article_dense_id in article_mask.iter() {
+                        // distinct get is O(1)
+                        // We use get unchecked for max speed if we are sure indices are valid
+                        if let Some(views) = day_data.get(article_dense_id as usize) {
+                            daily_total += views as u64;
+                        }
                     }
+                    results.push((curr, daily_total));
+                } else {
+                    results.push((curr, 0));
                 }
-                results.push((curr, daily_total));
-            } else {
-                results.push((curr, 0));
+                curr = curr.succ_opt().unwrap();
             }
-            curr = curr.succ_opt().unwrap();
-        }
 
+            results
+        })();
+
+        self.profiler
+            .phase("get_category_trend", "end", &key, query_start.elapsed());
         results
     }
 
     /// Calculate the total pageviews for a set of articles over time.
     pub fn get_article_trend(
-        &mut self,
+        &self,
         article_qid: u32,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Vec<(NaiveDate, u64)> {
-        let mut results = Vec::new();
+        let query_start = Instant::now();
+        let key = format!("article_qid={article_qid} start={start_date} end={end_date}");
+        self.profiler.event("get_article_trend", "start", &key);
 
-        let article_dense_id = match self.wikigraph.art_original_to_dense.get(article_qid) {
-            Some(dense_id) => dense_id,
-            None => {
+        let results = (|| {
+            let mut results = Vec::new();
+
+            let article_dense_id = match self.wikigraph.art_original_to_dense.get(article_qid) {
+                Some(dense_id) => dense_id,
+                None => {
+                    eprintln!(
+                        "Could not find dense id for article: {}/{}",
+                        self.wiki, &article_qid
+                    );
+                    return vec![];
+                }
+            };
+
+            let mut article_mask: RoaringBitmap = RoaringBitmap::new();
+
+            article_mask.insert(article_dense_id);
+
+            // Optimization: If mask is empty, return early
+            if article_mask.is_empty() {
                 eprintln!(
-                    "Could not find dense id for article: {}/{}",
+                    "Could not find articles in category: {}/{}",
                     self.wiki, &article_qid
                 );
                 return vec![];
             }
-        };
-
-        let mut article_mask: RoaringBitmap = RoaringBitmap::new();
-
-        article_mask.insert(article_dense_id);
-
-        // Optimization: If mask is empty, return early
-        if article_mask.is_empty() {
-            eprintln!(
-                "Could not find articles in category: {}/{}",
-                self.wiki, &article_qid
-            );
-            return vec![];
-        }
-        // println!(
-        //     "Found {} articles in category {}/{}",
-        //     article_mask.len(),
-        //     self.wiki,
-        //     &article
-        // );
-        let mut curr: NaiveDate = start_date;
+            // println!(
+            //     "Found {} articles in category {}/{}",
+            //     article_mask.len(),
+            //     self.wiki,
+            //     &article
+            // );
+            let mut curr: NaiveDate = start_date;
 
-        self.load_history_for_date_range(start_date, end_date)
-            .expect("Error in loading pageview history");
+            self.load_history_for_date_range(start_date, end_date)
+                .expect("Error in loading pageview history");
 
-        while curr <= end_date {
-            match self.daily_views.get(&curr) {
-                Some(day_data) => {
-                    let mut daily_total: u64 = 0;
-                    for article_dense_id in article_mask.iter() {
-                        // distinct get is O(1)
-                        // We use get unchecked for max speed if we are sure indices are valid
-                        if let Some(&views) = day_data.get(article_dense_id as usize) {
-                            daily_total += views as u64;
+            let daily_views = self.daily_views.read().unwrap();
+            while curr <= end_date {
+                match daily_views.get(&curr) {
+                    Some(day_data) => {
+                        let mut daily_total: u64 = 0;
+                        for article_dense_id in article_mask.iter() {
+                            // distinct get is O(1)
+                            // We use get unchecked for max speed if we are sure indices are valid
+                            if let Some(views) = day_data.get(article_dense_id as usize) {
+                                daily_total += views as u64;
+                            }
                         }
+                        results.push((curr, daily_total));
+                    }
+                    None => {
+                        //eprintln!("Daily views for {} is not available", curr);
+                        results.push((curr, 0));
                     }
-                    results.push((curr, daily_total));
-                }
-                None => {
-                    //eprintln!("Daily views for {} is not available", curr);
-                    results.push((curr, 0));
                 }
+                curr = curr.succ_opt().unwrap();
             }
-            curr = curr.succ_opt().unwrap();
-        }
+            results
+        })();
+
+        self.profiler
+            .phase("get_article_trend", "end", &key, query_start.elapsed());
         results
     }
 
+    /// Ensures every day in `[start_date, end_date]` is present in
+    /// `daily_views`, batch-loading whatever's missing from `store` in one
+    /// call instead of one file/query per day.
     pub fn load_history_for_date_range(
-        &mut self,
+        &self,
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<(), Box<dyn Error>> {
-        let mut curr_date = start_date;
-
-        while curr_date <= end_date {
-            if !self.daily_views.contains_key(&curr_date) {
-                // Attempt to load the data for the date if not in cache
-                if let Some(day_vec) = self.load_daily_view(curr_date)? {
-                    self.daily_views.insert(curr_date, day_vec);
+        let fully_cached = {
+            let daily_views = self.daily_views.read().unwrap();
+            let mut curr_date = start_date;
+            let mut cached = true;
+            while curr_date <= end_date {
+                if !daily_views.contains_key(&curr_date) {
+                    cached = false;
+                    break;
                 }
+                curr_date = curr_date.succ_opt().unwrap();
             }
-            curr_date = curr_date.succ_opt().unwrap();
-        }
-
-        Ok(())
-    }
+            cached
+        };
 
-    fn load_daily_view(&self, date: NaiveDate) -> Result<Option<Vec<u32>>, Box<dyn Error>> {
-        let num_articles = self.wikigraph.art_dense_to_original.len();
+        if fully_cached {
+            return Ok(());
+        }
 
-        let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
-        let bin_filename = format!(
-            "{}/{}/pageviews/{}/{:02}/{:02}.bin",
-            data_dir,
-            self.wiki,
-            date.year(),
-            date.month(),
-            date.day()
-        );
+        let query_start = Instant::now();
+        let key = format!("wiki={} start={start_date} end={end_date}", self.wiki);
+        self.profiler
+            .event("load_history_for_date_range", "start", &key);
 
-        if !std::path::Path::new(&bin_filename).exists() {
-            // eprintln!(
-            //     "Could not find page view data for {} at {}",
-            //     date, bin_filename
-            // );
-            return Ok(None);
+        let loaded = self.store.load_range(&self.wiki, start_date, end_date)?;
+        let mut daily_views = self.daily_views.write().unwrap();
+        for (date, day_vec) in loaded {
+            daily_views.entry(date).or_insert(day_vec);
         }
 
-        let day_vec = load_bin_file(&bin_filename, num_articles)
-            .expect("Error reading the pageview bin file");
-        println!(
-            "Loaded page views for {} on {}, found {} articles",
-            self.wiki,
-            date,
-            day_vec.len()
+        self.profiler.phase(
+            "load_history_for_date_range",
+            "end",
+            &key,
+            query_start.elapsed(),
         );
-
-        Ok(Some(day_vec))
+        Ok(())
     }
 
     /// Clear the top categories cache
-    pub fn clear_top_categories_cache(&mut self) {
-        self.top_categories_cache.clear();
+    pub fn clear_top_categories_cache(&self) {
+        self.top_categories_cache.lock().unwrap().clear();
+    }
+
+    /// Spawns a [`TopCategoriesCacheWorker`] that periodically evicts
+    /// expired (and, if `max_entries` is set, over-budget) entries from this
+    /// engine's top-categories cache on its own schedule, independent of
+    /// query traffic. The caller owns the returned worker and is
+    /// responsible for eventually calling `.stop()` on it (dropping it also
+    /// stops the thread).
+    pub fn start_top_categories_cache_eviction_worker(
+        &self,
+        scan_interval: Duration,
+        max_entries: Option<usize>,
+    ) -> TopCategoriesCacheWorker {
+        TopCategoriesCacheWorker::start(
+            Arc::clone(&self.top_categories_cache),
+            scan_interval,
+            max_entries,
+        )
     }
 
     /// Returns top N categories by DIRECT article views for a date range.
     pub fn get_top_categories(
-        &mut self,
+        &self,
         start_date: NaiveDate,
         end_date: NaiveDate,
         top_n: usize,
     ) -> Result<Vec<CategoryRank>, Box<dyn Error>> {
+        let query_start = Instant::now();
+        let key = format!("start={start_date} end={end_date} top_n={top_n}");
+        self.profiler.event("get_top_categories", "start", &key);
+
         // Check cache first
         let cache_key = TopCategoriesCacheKey {
             start: start_date,
@@ -378,12 +1054,16 @@ impl PageViewEngine {
             top_n,
         };
 
-        if let Some(cached_result) = self.top_categories_cache.get(&cache_key) {
+        if let Some(cached_result) = self.top_categories_cache.lock().unwrap().get(&cache_key) {
             println!("Cache hit for top_categories query: {:?}", cache_key);
+            self.profiler.event("get_top_categories", "cache_hit", &key);
+            self.profiler
+                .phase("get_top_categories", "end", &key, query_start.elapsed());
             return Ok(cached_result);
         }
 
         println!("Cache miss for top_categories query: {:?}", cache_key);
+        self.profiler.event("get_top_categories", "cache_miss", &key);
 
         let num_articles = self.wikigraph.art_dense_to_original.len(); // Approx 7M for
         // enwiki
@@ -392,26 +1072,33 @@ impl PageViewEngine {
         // Phase 1: Aggregation (Sum relevant days)
         // We create a temporary view vector for the range.
         // We can parallelize this sum if the range is huge, but usually linear is fine.
+        let phase_start = Instant::now();
         let mut article_views = vec![0u32; num_articles];
 
         self.load_history_for_date_range(start_date, end_date)
             .expect("Error in loading pageview history");
 
-        let mut curr = start_date;
-        while curr <= end_date {
-            if let Some(day_vec) = self.daily_views.get(&curr) {
-                // Vectorized addition (compiler auto-vectorizes this loop)
-                for (article_dense_id, &views) in day_vec.iter().enumerate() {
-                    article_views[article_dense_id] += views;
+        {
+            let daily_views = self.daily_views.read().unwrap();
+            let mut curr = start_date;
+            while curr <= end_date {
+                if let Some(day_vec) = daily_views.get(&curr) {
+                    // Vectorized addition (compiler auto-vectorizes this loop)
+                    for (article_dense_id, views) in day_vec.iter_nonzero() {
+                        article_views[article_dense_id] += views;
+                    }
                 }
+                curr = curr.succ_opt().unwrap();
             }
-            curr = curr.succ_opt().unwrap();
         }
+        self.profiler
+            .phase("get_top_categories", "aggregation", &key, phase_start.elapsed());
 
         // Phase 2: Scatter (Article -> Category)
         // We need an atomic accumulator or thread-local storage for parallel write.
         // For simplicity/speed balance, a single-threaded scatter is often fast enough
         // because it avoids synchronization overhead.
+        let phase_start = Instant::now();
         let mut cat_scores = vec![0u64; num_cats];
         let mut cat_articles: Vec<Vec<(u32, u32)>> = vec![Vec::new(); num_cats];
 
@@ -431,9 +1118,12 @@ impl PageViewEngine {
                 cat_articles[cat_dense_id as usize].push((art_dense_id as u32, views));
             }
         }
+        self.profiler
+            .phase("get_top_categories", "scatter", &key, phase_start.elapsed());
 
         // Phase 3: Sort & Top N
         // Create a list of indices to sort
+        let phase_start = Instant::now();
         let mut ranked: Vec<usize> = (0..num_cats).collect();
 
         // Parallel sort is overkill for 2.5M integers, standard sort is fine.
@@ -466,15 +1156,250 @@ impl PageViewEngine {
                 }
             })
             .collect();
+        self.profiler
+            .phase("get_top_categories", "sort", &key, phase_start.elapsed());
 
         // Cache the result
-        self.top_categories_cache.insert(cache_key, results.clone());
+        self.top_categories_cache
+            .lock()
+            .unwrap()
+            .insert(cache_key, results.clone());
 
+        self.profiler
+            .phase("get_top_categories", "end", &key, query_start.elapsed());
         Ok(results)
     }
 
+    /// Incremental counterpart to [`Self::get_top_categories`]: instead of
+    /// re-scattering every article into every category on each call, keeps a
+    /// persistent [`IncrementalWindow`] and, when the requested window
+    /// overlaps the previous one, applies only the signed per-day deltas for
+    /// the days that entered/left the range. Re-ranking then only needs to
+    /// consider categories whose score actually changed plus the previous
+    /// top-N, not all categories - see `IncrementalWindow::top_ids`.
+    ///
+    /// Falls back to a full rebuild the first time it's called, or whenever
+    /// the graph's article/category counts no longer match the cached state
+    /// (e.g. after a graph reload).
+    pub fn get_top_categories_incremental(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        top_n: usize,
+    ) -> Result<Vec<CategoryRank>, Box<dyn Error>> {
+        let query_start = Instant::now();
+        let key = format!("start={start_date} end={end_date} top_n={top_n}");
+        self.profiler
+            .event("get_top_categories_incremental", "start", &key);
+
+        self.load_history_for_date_range(start_date, end_date)?;
+
+        let num_articles = self.wikigraph.art_dense_to_original.len();
+        let num_cats = self.wikigraph.cat_dense_to_original.len();
+
+        let mut state_guard = self.incremental_top_categories.lock().unwrap();
+        let dirty: HashSet<usize> = match state_guard.as_mut() {
+            Some(state)
+                if state.article_views.len() == num_articles
+                    && state.cat_scores.len() == num_cats =>
+            {
+                self.apply_window_shift(state, start_date, end_date)
+            }
+            _ => {
+                let state = self.rebuild_window(start_date, end_date, num_articles, num_cats);
+                let dirty = (0..num_cats).filter(|&id| state.cat_scores[id] > 0).collect();
+                *state_guard = Some(state);
+                dirty
+            }
+        };
+
+        let state = state_guard.as_mut().unwrap();
+
+        // Re-rank: the new top-N can only be drawn from categories whose
+        // score just changed, or categories that were already in the
+        // previous top-N (and so might now have fallen out of it) - unless a
+        // previous top-N member changed (it may have fallen below an
+        // unchanged outsider) or the previous list is shorter than `top_n`,
+        // in which case every category is re-ranked.
+        let mut candidates: Vec<usize> = if state.top_ids.len() < top_n
+            || state.top_ids.iter().any(|id| dirty.contains(id))
+        {
+            (0..num_cats).collect()
+        } else {
+            state.top_ids.iter().copied().collect()
+        };
+        candidates.extend(dirty.iter().copied());
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.sort_by(|&a, &b| state.cat_scores[b].cmp(&state.cat_scores[a]));
+        candidates.retain(|&id| state.cat_scores[id] > 0);
+        candidates.truncate(top_n);
+        state.top_ids = candidates.clone();
+
+        let results: Vec<CategoryRank> = candidates
+            .into_iter()
+            .map(|cat_dense_id| {
+                let mut articles: Vec<(u32, u32)> = state.cat_articles[cat_dense_id]
+                    .iter()
+                    .map(|(&a, &v)| (a, v))
+                    .collect();
+                articles.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+
+                let top_articles: Vec<ArticleRank> = articles
+                    .into_iter()
+                    .take(top_n)
+                    .map(|(art_dense_id, views)| ArticleRank {
+                        article_qid: self.wikigraph.art_dense_to_original[art_dense_id as usize],
+                        total_views: views as u64,
+                    })
+                    .collect();
+
+                CategoryRank {
+                    category_qid: self.wikigraph.cat_dense_to_original[cat_dense_id],
+                    total_views: state.cat_scores[cat_dense_id],
+                    top_articles,
+                }
+            })
+            .collect();
+
+        drop(state_guard);
+        self.profiler.phase(
+            "get_top_categories_incremental",
+            "end",
+            &key,
+            query_start.elapsed(),
+        );
+        Ok(results)
+    }
+
+    /// Builds a fresh [`IncrementalWindow`] for `[start_date, end_date]` by
+    /// scanning every cached day in the range, the same aggregation/scatter
+    /// work `get_top_categories` does, but keeping the per-category article
+    /// lists around afterward so later window shifts can patch them.
+    fn rebuild_window(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        num_articles: usize,
+        num_cats: usize,
+    ) -> IncrementalWindow {
+        let mut article_views = vec![0u32; num_articles];
+        {
+            let daily_views = self.daily_views.read().unwrap();
+            let mut curr = start_date;
+            while curr <= end_date {
+                if let Some(day_vec) = daily_views.get(&curr) {
+                    for (article_dense_id, views) in day_vec.iter_nonzero() {
+                        article_views[article_dense_id] += views;
+                    }
+                }
+                curr = curr.succ_opt().unwrap();
+            }
+        }
+
+        let mut cat_scores = vec![0u64; num_cats];
+        let mut cat_articles: Vec<HashMap<u32, u32>> = vec![HashMap::new(); num_cats];
+        for (art_dense_id, &views) in article_views.iter().enumerate() {
+            if views == 0 {
+                continue;
+            }
+            let article_categories = self.wikigraph.article_cats.get(art_dense_id as u32);
+            for &cat_dense_id in article_categories {
+                cat_scores[cat_dense_id as usize] += views as u64;
+                cat_articles[cat_dense_id as usize].insert(art_dense_id as u32, views);
+            }
+        }
+
+        IncrementalWindow {
+            start: start_date,
+            end: end_date,
+            article_views,
+            cat_scores,
+            cat_articles,
+            top_ids: Vec::new(),
+        }
+    }
+
+    /// Moves `state`'s window to `[new_start, new_end]` by applying a `-1`
+    /// delta for days that left the range and a `+1` delta for days that
+    /// entered it, returning the set of dense category ids whose score
+    /// changed as a result.
+    fn apply_window_shift(
+        &self,
+        state: &mut IncrementalWindow,
+        new_start: NaiveDate,
+        new_end: NaiveDate,
+    ) -> HashSet<usize> {
+        let expiring = days_in_range_excluding(state.start, state.end, new_start, new_end);
+        let newly_included = days_in_range_excluding(new_start, new_end, state.start, state.end);
+
+        let mut dirty = HashSet::new();
+        let daily_views = self.daily_views.read().unwrap();
+        for day in expiring {
+            if let Some(day_vec) = daily_views.get(&day) {
+                self.apply_day_delta(state, day_vec, false, &mut dirty);
+            }
+        }
+        for day in newly_included {
+            if let Some(day_vec) = daily_views.get(&day) {
+                self.apply_day_delta(state, day_vec, true, &mut dirty);
+            }
+        }
+        drop(daily_views);
+
+        state.start = new_start;
+        state.end = new_end;
+        dirty
+    }
+
+    /// Applies one day's view vector as a signed delta (`add = true` to
+    /// include it, `false` to expire it) to `state.article_views` and
+    /// `state.cat_scores`, propagating through the Article->Category CSR.
+    /// Uses saturating arithmetic so the running totals never wrap.
+    fn apply_day_delta(
+        &self,
+        state: &mut IncrementalWindow,
+        day_vec: &DailyViews,
+        add: bool,
+        dirty: &mut HashSet<usize>,
+    ) {
+        for (art_dense_id, views) in day_vec.iter_nonzero() {
+            if add {
+                state.article_views[art_dense_id] =
+                    state.article_views[art_dense_id].saturating_add(views);
+            } else {
+                state.article_views[art_dense_id] =
+                    state.article_views[art_dense_id].saturating_sub(views);
+            }
+
+            let article_categories = self.wikigraph.article_cats.get(art_dense_id as u32);
+            for &cat_dense_id in article_categories {
+                let cid = cat_dense_id as usize;
+                if add {
+                    state.cat_scores[cid] = state.cat_scores[cid].saturating_add(views as u64);
+                    *state.cat_articles[cid]
+                        .entry(art_dense_id as u32)
+                        .or_insert(0) += views;
+                } else {
+                    state.cat_scores[cid] = state.cat_scores[cid].saturating_sub(views as u64);
+                    if let std::collections::hash_map::Entry::Occupied(mut e) =
+                        state.cat_articles[cid].entry(art_dense_id as u32)
+                    {
+                        let remaining = e.get().saturating_sub(views);
+                        if remaining == 0 {
+                            e.remove();
+                        } else {
+                            *e.get_mut() = remaining;
+                        }
+                    }
+                }
+                dirty.insert(cid);
+            }
+        }
+    }
+
     pub fn get_top_articles_in_category(
-        &mut self,
+        &self,
         category_qid: u32,
         start_date: NaiveDate,
         end_date: NaiveDate,
@@ -499,14 +1424,15 @@ impl PageViewEngine {
 
         // Aggregate views for each article
         let mut article_views: Vec<(u32, u64)> = Vec::new();
+        let daily_views = self.daily_views.read().unwrap();
 
         for article_dense_id in article_mask.iter() {
             let mut total_views = 0u64;
 
             let mut curr = start_date;
             while curr <= end_date {
-                if let Some(day_data) = self.daily_views.get(&curr) {
-                    if let Some(&views) = day_data.get(article_dense_id as usize) {
+                if let Some(day_data) = daily_views.get(&curr) {
+                    if let Some(views) = day_data.get(article_dense_id as usize) {
                         total_views += views as u64;
                     }
                 }
@@ -542,3 +1468,191 @@ impl PageViewEngine {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direct_map::DirectMap;
+
+    /// Builds a dense [`DailyViews`] day from a raw per-article view count
+    /// slice, using the same "VIEW" header `parse_daily_views` expects, so
+    /// tests don't have to go through a real `.bin` file.
+    fn dense_day(views: &[u32]) -> DailyViews {
+        let mut bytes = Vec::with_capacity(16 + views.len() * std::mem::size_of::<u32>());
+        bytes.extend_from_slice(b"VIEW");
+        bytes.extend_from_slice(&DAILY_VIEWS_VERSION_DENSE.to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 8]);
+        for &v in views {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        parse_daily_views(ViewBytes::Owned(Arc::new(bytes)), views.len())
+    }
+
+    /// Builds a sparse [`DailyViews`] day from sorted `(dense_id, views)`
+    /// pairs, optionally dictionary-encoding the values (`dict`) the same
+    /// way the sparse `.bin` dumper does, so tests can exercise both the
+    /// raw-`u32` and dictionary-indexed value encodings `read_sparse_daily_views`
+    /// parses.
+    fn sparse_day(entries: &[(u32, u32)], dict: Option<&[u32]>, expected_size: usize) -> DailyViews {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"VIEW");
+        bytes.extend_from_slice(&DAILY_VIEWS_VERSION_SPARSE.to_le_bytes());
+        bytes.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+
+        let dict_len = dict.map_or(0, |d| d.len()) as u32;
+        bytes.extend_from_slice(&dict_len.to_le_bytes());
+        for &(id, _) in entries {
+            bytes.extend_from_slice(&id.to_le_bytes());
+        }
+        match dict {
+            Some(dict) => {
+                for &v in dict {
+                    bytes.extend_from_slice(&v.to_le_bytes());
+                }
+                for &(_, v) in entries {
+                    let idx = dict.iter().position(|&d| d == v).unwrap() as u16;
+                    bytes.extend_from_slice(&idx.to_le_bytes());
+                }
+            }
+            None => {
+                for &(_, v) in entries {
+                    bytes.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+
+        parse_daily_views(ViewBytes::Owned(Arc::new(bytes)), expected_size)
+    }
+
+    #[test]
+    fn sparse_daily_views_round_trips_raw_values() {
+        let entries = [(1u32, 10u32), (3, 7), (4, 99)];
+        let day = sparse_day(&entries, None, 5);
+
+        assert_eq!(day.len(), entries.len());
+        for &(id, views) in &entries {
+            assert_eq!(day.get(id as usize), Some(views));
+        }
+        assert_eq!(day.get(0), None);
+        assert_eq!(day.get(2), None);
+
+        let collected: Vec<(usize, u32)> = day.iter_nonzero().collect();
+        assert_eq!(
+            collected,
+            entries.iter().map(|&(id, v)| (id as usize, v)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sparse_daily_views_round_trips_dictionary_encoded_values() {
+        let dict = [5u32, 42, 1000];
+        let entries = [(0u32, 42u32), (2, 5), (6, 1000), (7, 42)];
+        let day = sparse_day(&entries, Some(&dict), 10);
+
+        assert_eq!(day.len(), entries.len());
+        for &(id, views) in &entries {
+            assert_eq!(day.get(id as usize), Some(views));
+        }
+
+        let collected: Vec<(usize, u32)> = day.iter_nonzero().collect();
+        assert_eq!(
+            collected,
+            entries.iter().map(|&(id, v)| (id as usize, v)).collect::<Vec<_>>()
+        );
+    }
+
+    /// A fixed set of pre-baked days, served straight out of a `HashMap`
+    /// instead of touching disk, so the incremental-vs-full-rebuild test
+    /// can exercise several window shifts deterministically.
+    struct FixedPageViewStore {
+        days: HashMap<NaiveDate, DailyViews>,
+    }
+
+    impl PageViewStore for FixedPageViewStore {
+        fn load_day(&self, _wiki: &str, date: NaiveDate) -> Result<Option<DailyViews>, Box<dyn Error>> {
+            Ok(self.days.get(&date).cloned())
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    /// Two articles under one category, with per-day view counts chosen so
+    /// that repeated window shifts repeatedly add/remove the *same* article
+    /// at different view counts - the scenario that used to leave stale,
+    /// un-merged `(art_dense_id, views)` duplicates behind.
+    fn build_engine() -> PageViewEngine {
+        let children = crate::csr_adjacency::CsrAdjacency::from_pairs(1, &[]);
+        let parents = crate::csr_adjacency::CsrAdjacency::from_pairs(1, &[]);
+        let article_cats = crate::csr_adjacency::CsrAdjacency::from_pairs(2, &[(0, 0), (1, 0)]);
+
+        let mut cat_bitmap = RoaringBitmap::new();
+        cat_bitmap.insert(0);
+        cat_bitmap.insert(1);
+
+        let mut cat_original_to_dense = DirectMap::new(200);
+        cat_original_to_dense.insert(200, 0);
+        let mut art_original_to_dense = DirectMap::new(101);
+        art_original_to_dense.insert(100, 0);
+        art_original_to_dense.insert(101, 1);
+
+        let wikigraph = WikiGraph {
+            children,
+            parents,
+            cat_articles: vec![cat_bitmap],
+            article_cats,
+            cat_dense_to_original: vec![200],
+            cat_original_to_dense,
+            art_dense_to_original: vec![100, 101],
+            art_original_to_dense,
+        };
+
+        let days = HashMap::from([
+            (date(1), dense_day(&[10, 5])),
+            (date(2), dense_day(&[3, 8])),
+            (date(3), dense_day(&[1, 1])),
+            (date(4), dense_day(&[20, 0])),
+            (date(5), dense_day(&[0, 9])),
+            (date(6), dense_day(&[4, 4])),
+        ]);
+
+        PageViewEngine::with_store("testwiki", wikigraph, Box::new(FixedPageViewStore { days }))
+    }
+
+    fn assert_same_ranking(incremental: &[CategoryRank], full: &[CategoryRank]) {
+        assert_eq!(incremental.len(), full.len());
+        for (inc, full) in incremental.iter().zip(full.iter()) {
+            assert_eq!(inc.category_qid, full.category_qid);
+            assert_eq!(inc.total_views, full.total_views);
+            assert_eq!(
+                inc.top_articles.iter().map(|a| (a.article_qid, a.total_views)).collect::<Vec<_>>(),
+                full.top_articles.iter().map(|a| (a.article_qid, a.total_views)).collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_top_categories_matches_full_rebuild_after_window_shifts() {
+        let engine = build_engine();
+
+        // Each window overlaps the previous one by one day, so every call
+        // after the first goes through `apply_window_shift` rather than
+        // `rebuild_window`.
+        let windows = [
+            (date(1), date(2)),
+            (date(2), date(3)),
+            (date(3), date(4)),
+            (date(4), date(5)),
+            (date(5), date(6)),
+        ];
+
+        for (start, end) in windows {
+            let incremental = engine
+                .get_top_categories_incremental(start, end, 10)
+                .unwrap();
+            let full = engine.get_top_categories(start, end, 10).unwrap();
+            assert_same_ranking(&incremental, &full);
+        }
+    }
+}
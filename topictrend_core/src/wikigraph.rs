@@ -1,6 +1,7 @@
 use crate::{csr_adjacency::CsrAdjacency, direct_map::DirectMap};
 use roaring::RoaringBitmap;
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 
 /// The core high-performance graph structure.
 /// All internal logic uses "Dense IDs" (0..N), not the raw Wikipedia Page QIDs.
@@ -17,6 +18,35 @@ pub struct WikiGraph {
 }
 
 impl WikiGraph {
+    /// Number of article and category nodes, for graph-size metrics.
+    pub fn node_counts(&self) -> (usize, usize) {
+        (self.art_dense_to_original.len(), self.cat_dense_to_original.len())
+    }
+
+    /// Number of category->category edges, for graph-size metrics.
+    pub fn edge_count(&self) -> usize {
+        self.children.edge_count()
+    }
+
+    /// Approximate total heap size of the graph's CSR arrays, bitmaps, and
+    /// dense/original ID vectors, for the engine cache's memory budget.
+    pub fn approx_memory_bytes(&self) -> usize {
+        let bitmaps_bytes: usize = self
+            .cat_articles
+            .iter()
+            .map(|bitmap| bitmap.serialized_size())
+            .sum();
+
+        self.children.memory_bytes()
+            + self.parents.memory_bytes()
+            + self.article_cats.memory_bytes()
+            + bitmaps_bytes
+            + self.cat_dense_to_original.len() * std::mem::size_of::<u32>()
+            + self.cat_original_to_dense.memory_bytes()
+            + self.art_dense_to_original.len() * std::mem::size_of::<u32>()
+            + self.art_original_to_dense.memory_bytes()
+    }
+
     /// Find all articles in a category (and optionally subcategories to depth N)
     pub fn get_articles_in_category(
         &self,
@@ -167,4 +197,330 @@ impl WikiGraph {
             })
             .collect())
     }
+
+    /// Shortest path between two categories via bidirectional BFS, one
+    /// frontier walking forward from `a` and one walking backward from `b`,
+    /// expanding whichever frontier is smaller each round. `forward` picks
+    /// which adjacency the forward search walks (`children` if true,
+    /// `parents` if false); the backward search always walks the other one,
+    /// so both halves traverse the same direction of the graph. Each
+    /// frontier's visited set guards against the category graph's known
+    /// cycles. `max_depth` bounds the total stitched path length; `None`
+    /// searches unbounded. Returns `None` if the frontiers never meet
+    /// within that bound.
+    pub fn shortest_category_path(
+        &self,
+        a_qid: u32,
+        b_qid: u32,
+        forward: bool,
+        max_depth: Option<u8>,
+    ) -> Option<Vec<u32>> {
+        let a_dense = self.cat_original_to_dense.get(a_qid)?;
+        let b_dense = self.cat_original_to_dense.get(b_qid)?;
+
+        if a_dense == b_dense {
+            return Some(vec![a_qid]);
+        }
+
+        let (fwd_adj, bwd_adj) = if forward {
+            (&self.children, &self.parents)
+        } else {
+            (&self.parents, &self.children)
+        };
+
+        let mut fwd_visited = RoaringBitmap::new();
+        let mut bwd_visited = RoaringBitmap::new();
+        fwd_visited.insert(a_dense);
+        bwd_visited.insert(b_dense);
+
+        let mut fwd_parent_of: HashMap<u32, u32> = HashMap::new();
+        let mut bwd_parent_of: HashMap<u32, u32> = HashMap::new();
+
+        let mut fwd_frontier = vec![a_dense];
+        let mut bwd_frontier = vec![b_dense];
+        let mut fwd_hops: u32 = 0;
+        let mut bwd_hops: u32 = 0;
+
+        let meeting_node = 'search: loop {
+            if fwd_frontier.is_empty() || bwd_frontier.is_empty() {
+                return None;
+            }
+
+            // The two halves' hop counts bound the total path length once
+            // stitched together; stop expanding once no combination could
+            // still land within `max_depth`.
+            if let Some(limit) = max_depth {
+                if fwd_hops + bwd_hops >= limit as u32 {
+                    return None;
+                }
+            }
+
+            if fwd_frontier.len() <= bwd_frontier.len() {
+                fwd_hops += 1;
+                let mut next_frontier = Vec::new();
+                for node in fwd_frontier.drain(..) {
+                    for &neighbor in fwd_adj.get(node) {
+                        if !fwd_visited.contains(neighbor) {
+                            fwd_visited.insert(neighbor);
+                            fwd_parent_of.insert(neighbor, node);
+                            if bwd_visited.contains(neighbor) {
+                                break 'search neighbor;
+                            }
+                            next_frontier.push(neighbor);
+                        }
+                    }
+                }
+                fwd_frontier = next_frontier;
+            } else {
+                bwd_hops += 1;
+                let mut next_frontier = Vec::new();
+                for node in bwd_frontier.drain(..) {
+                    for &neighbor in bwd_adj.get(node) {
+                        if !bwd_visited.contains(neighbor) {
+                            bwd_visited.insert(neighbor);
+                            bwd_parent_of.insert(neighbor, node);
+                            if fwd_visited.contains(neighbor) {
+                                break 'search neighbor;
+                            }
+                            next_frontier.push(neighbor);
+                        }
+                    }
+                }
+                bwd_frontier = next_frontier;
+            }
+        };
+
+        // Stitch the two half-paths at the meeting node: walk fwd_parent_of
+        // back to `a`, then bwd_parent_of forward to `b`.
+        let mut path_dense = vec![meeting_node];
+
+        let mut cursor = meeting_node;
+        while let Some(&parent) = fwd_parent_of.get(&cursor) {
+            path_dense.push(parent);
+            cursor = parent;
+        }
+        path_dense.reverse();
+
+        let mut cursor = meeting_node;
+        while let Some(&parent) = bwd_parent_of.get(&cursor) {
+            path_dense.push(parent);
+            cursor = parent;
+        }
+
+        Some(
+            path_dense
+                .into_iter()
+                .map(|dense| self.cat_dense_to_original[dense as usize])
+                .collect(),
+        )
+    }
+
+    /// Category reachable from both `a_qid` and `b_qid` by walking `parents`
+    /// upward from each, with the smallest combined depth from both starting
+    /// categories. Which one is returned among ancestors with equal combined
+    /// depth is not defined. Returns `None` if they share no common ancestor.
+    pub fn lowest_common_ancestor(&self, a_qid: u32, b_qid: u32) -> Option<u32> {
+        let a_dense = self.cat_original_to_dense.get(a_qid)?;
+        let b_dense = self.cat_original_to_dense.get(b_qid)?;
+
+        if a_dense == b_dense {
+            return Some(a_qid);
+        }
+
+        let a_depths = self.ancestor_depths(a_dense, None);
+        let b_depths = self.ancestor_depths(b_dense, None);
+
+        a_depths
+            .iter()
+            .filter_map(|(&node, &depth_a)| {
+                b_depths
+                    .get(&node)
+                    .map(|&depth_b| (node, depth_a + depth_b))
+            })
+            .min_by_key(|&(_, combined_depth)| combined_depth)
+            .map(|(node, _)| self.cat_dense_to_original[node as usize])
+    }
+
+    /// All categories reachable upward via `parents` from both `a_qid` and
+    /// `b_qid`, as `(ancestor_qid, combined_depth)` pairs ordered nearest
+    /// first (ascending combined depth from both starting categories).
+    /// `max_depth` bounds how far up each side's BFS climbs before looking
+    /// for a shared ancestor; `None` climbs to the root. Returns an empty
+    /// vector if they share no common ancestor within that bound.
+    pub fn common_ancestors(
+        &self,
+        a_qid: u32,
+        b_qid: u32,
+        max_depth: Option<u8>,
+    ) -> Vec<(u32, u32)> {
+        let (Some(a_dense), Some(b_dense)) = (
+            self.cat_original_to_dense.get(a_qid),
+            self.cat_original_to_dense.get(b_qid),
+        ) else {
+            return Vec::new();
+        };
+
+        if a_dense == b_dense {
+            return vec![(a_qid, 0)];
+        }
+
+        let a_depths = self.ancestor_depths(a_dense, max_depth);
+        let b_depths = self.ancestor_depths(b_dense, max_depth);
+
+        let mut shared: Vec<(u32, u32)> = a_depths
+            .iter()
+            .filter_map(|(&node, &depth_a)| {
+                b_depths
+                    .get(&node)
+                    .map(|&depth_b| (self.cat_dense_to_original[node as usize], depth_a + depth_b))
+            })
+            .collect();
+
+        shared.sort_by_key(|&(_, combined_depth)| combined_depth);
+        shared
+    }
+
+    /// Depth-labeled BFS upward through `parents`, from `start` (depth 0),
+    /// optionally stopping once `max_depth` is reached.
+    fn ancestor_depths(&self, start: u32, max_depth: Option<u8>) -> HashMap<u32, u32> {
+        let mut depths = HashMap::new();
+        let mut queue = VecDeque::new();
+        depths.insert(start, 0);
+        queue.push_back(start);
+
+        while let Some(node) = queue.pop_front() {
+            let depth = depths[&node];
+            if max_depth.is_some_and(|limit| depth >= limit as u32) {
+                continue;
+            }
+            for &parent in self.parents.get(node) {
+                if !depths.contains_key(&parent) {
+                    depths.insert(parent, depth + 1);
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        depths
+    }
+}
+
+/// One request in a [`WikiGraph::run_batch`] call. `op` selects the variant
+/// via serde's internally-tagged representation, mirroring the workload
+/// format in `topictrend_cli::bench::WorkloadOp` - a batch input file looks
+/// like newline-delimited
+/// `{"op":"list-articles","category_qid":1,"depth":2}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum BatchRequest {
+    ListArticles {
+        category_qid: u32,
+        #[serde(default)]
+        depth: u8,
+    },
+    ListChildCategories {
+        category_qid: u32,
+    },
+    ListDescendantCategories {
+        category_qid: u32,
+        #[serde(default)]
+        depth: u8,
+    },
+    ListParentCategories {
+        category_qid: u32,
+    },
+    ListArticleCategories {
+        article_qid: u32,
+    },
+}
+
+/// The result of one [`BatchRequest`], tagged the same way so a batch
+/// output line round-trips the same shape as its request plus the data (or
+/// an `error` variant if the lookup failed).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum BatchResult {
+    ListArticles {
+        category_qid: u32,
+        articles: Vec<u32>,
+    },
+    ListChildCategories {
+        category_qid: u32,
+        categories: Vec<u32>,
+    },
+    ListDescendantCategories {
+        category_qid: u32,
+        categories: Vec<(u32, u8)>,
+    },
+    ListParentCategories {
+        category_qid: u32,
+        categories: Vec<u32>,
+    },
+    ListArticleCategories {
+        article_qid: u32,
+        categories: Vec<u32>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl WikiGraph {
+    /// Resolves many [`BatchRequest`]s against this single already-loaded
+    /// graph, returning one [`BatchResult`] per request in the same order -
+    /// so a caller can resolve thousands of lookups without spawning a
+    /// process (and rebuilding the graph) per lookup.
+    pub fn run_batch(&self, requests: &[BatchRequest]) -> Vec<BatchResult> {
+        requests.iter().map(|request| self.run_one(request)).collect()
+    }
+
+    fn run_one(&self, request: &BatchRequest) -> BatchResult {
+        match *request {
+            BatchRequest::ListArticles { category_qid, depth } => {
+                match self.get_articles_in_category(category_qid, depth) {
+                    Ok(articles) => BatchResult::ListArticles {
+                        category_qid,
+                        articles: articles.iter().collect(),
+                    },
+                    Err(message) => BatchResult::Error { message },
+                }
+            }
+            BatchRequest::ListChildCategories { category_qid } => {
+                match self.get_child_categories(category_qid) {
+                    Ok(categories) => BatchResult::ListChildCategories {
+                        category_qid,
+                        categories,
+                    },
+                    Err(message) => BatchResult::Error { message },
+                }
+            }
+            BatchRequest::ListDescendantCategories { category_qid, depth } => {
+                match self.get_descendant_categories(category_qid, depth) {
+                    Ok(categories) => BatchResult::ListDescendantCategories {
+                        category_qid,
+                        categories,
+                    },
+                    Err(message) => BatchResult::Error { message },
+                }
+            }
+            BatchRequest::ListParentCategories { category_qid } => {
+                match self.get_parent_categories(category_qid) {
+                    Ok(categories) => BatchResult::ListParentCategories {
+                        category_qid,
+                        categories,
+                    },
+                    Err(message) => BatchResult::Error { message },
+                }
+            }
+            BatchRequest::ListArticleCategories { article_qid } => {
+                match self.get_categories_for_article(article_qid) {
+                    Ok(categories) => BatchResult::ListArticleCategories {
+                        article_qid,
+                        categories,
+                    },
+                    Err(message) => BatchResult::Error { message },
+                }
+            }
+        }
+    }
 }
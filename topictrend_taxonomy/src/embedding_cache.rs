@@ -0,0 +1,79 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+
+/// Stable key for a cached embedding: the embedding model together with the
+/// text it was computed from, so swapping models doesn't serve stale
+/// vectors under the same text.
+fn text_hash(model_id: &str, text: &str) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    model_id.hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish() as i64
+}
+
+fn cache_path() -> String {
+    std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string()) + "/embedding_cache.db"
+}
+
+/// Opens (creating if needed) the sqlite database backing the persistent
+/// embedding cache, shared across all wikis and callers.
+pub fn open() -> Result<Connection, Box<dyn Error>> {
+    let conn = Connection::open(cache_path())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS embedding_cache (
+            text_hash INTEGER PRIMARY KEY,
+            vector BLOB NOT NULL
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// Looks up `text`'s cached embedding for `model_id`, if one has already
+/// been computed and stored.
+pub fn get(conn: &Connection, model_id: &str, text: &str) -> Result<Option<Vec<f32>>, Box<dyn Error>> {
+    let vector = conn
+        .query_row(
+            "SELECT vector FROM embedding_cache WHERE text_hash = ?1",
+            params![text_hash(model_id, text)],
+            |row| {
+                let bytes: Vec<u8> = row.get(0)?;
+                Ok(bytes)
+            },
+        )
+        .optional()?;
+    Ok(vector.map(|bytes| decode_vector(&bytes)))
+}
+
+/// Atomically stores every `(text, vector)` pair in `entries`, in a single
+/// transaction, so a crash mid-batch never leaves a partially-written batch
+/// visible to later readers.
+pub fn put_batch(
+    conn: &mut Connection,
+    model_id: &str,
+    entries: &[(&str, Vec<f32>)],
+) -> Result<(), Box<dyn Error>> {
+    let tx = conn.transaction()?;
+    for (text, vector) in entries {
+        tx.execute(
+            "INSERT INTO embedding_cache (text_hash, vector) VALUES (?1, ?2)
+             ON CONFLICT(text_hash) DO UPDATE SET vector = excluded.vector",
+            params![text_hash(model_id, text), encode_vector(vector)],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
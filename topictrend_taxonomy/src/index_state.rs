@@ -0,0 +1,80 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use std::error::Error;
+
+/// Per-wiki indexing progress: the highest page id durably indexed so far,
+/// how many records that covers, and the size of the full corpus, so
+/// `injest` can skip already-indexed pages and resume after a crash instead
+/// of restarting from scratch.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexWatermark {
+    pub last_page_id: i64,
+    pub indexed_count: i64,
+    pub total_count: i64,
+}
+
+fn index_state_path() -> String {
+    std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string()) + "/index_state.db"
+}
+
+/// Opens (creating if needed) the sqlite database backing the index-state
+/// watermark table, shared across all wikis.
+pub fn open() -> Result<Connection, Box<dyn Error>> {
+    let conn = Connection::open(index_state_path())?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS index_watermark (
+            wiki TEXT PRIMARY KEY,
+            last_page_id INTEGER NOT NULL,
+            indexed_count INTEGER NOT NULL,
+            total_count INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(conn)
+}
+
+/// Reads `wiki`'s current watermark, if indexing has started for it.
+pub fn get_watermark(
+    conn: &Connection,
+    wiki: &str,
+) -> Result<Option<IndexWatermark>, Box<dyn Error>> {
+    let watermark = conn
+        .query_row(
+            "SELECT last_page_id, indexed_count, total_count FROM index_watermark WHERE wiki = ?1",
+            params![wiki],
+            |row| {
+                Ok(IndexWatermark {
+                    last_page_id: row.get(0)?,
+                    indexed_count: row.get(1)?,
+                    total_count: row.get(2)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(watermark)
+}
+
+/// Durably advances `wiki`'s watermark past a just-committed batch:
+/// `last_page_id` becomes the new resume point, `newly_indexed` is added to
+/// the running `indexed_count`, and `total_count` is refreshed to the
+/// corpus size observed this run. Wrapped in a transaction so a crash
+/// can't leave the watermark ahead of what was actually upserted to Qdrant.
+pub fn commit_batch(
+    conn: &mut Connection,
+    wiki: &str,
+    last_page_id: i64,
+    newly_indexed: i64,
+    total_count: i64,
+) -> Result<(), Box<dyn Error>> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO index_watermark (wiki, last_page_id, indexed_count, total_count)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(wiki) DO UPDATE SET
+            last_page_id = excluded.last_page_id,
+            indexed_count = indexed_count + ?3,
+            total_count = excluded.total_count",
+        params![wiki, last_page_id, newly_indexed, total_count],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use arrow_array::RecordBatch;
-use arrow_array::{Float32Array, RecordBatchIterator, StringArray};
+use arrow_array::{Float32Array, Int32Array, RecordBatchIterator, StringArray};
 use futures::StreamExt;
 use lancedb::{
     Connection,
@@ -8,39 +8,375 @@ use lancedb::{
         arrow_schema::{DataType, Field, Schema},
     },
     connect,
+    index::Index,
+    index::vector::IvfPqIndexBuilder,
     query::{ExecutableQuery, QueryBase},
 };
+use polars::prelude::*;
+use qdrant_client::Qdrant;
+use qdrant_client::qdrant::value::Kind;
+use qdrant_client::qdrant::{PointStruct, SearchPointsBuilder, UpsertPointsBuilder, Value};
+use std::collections::HashMap;
+use std::path::Path as FsPath;
 use std::{error::Error, sync::Arc};
 
-async fn search(
+pub mod embedding_cache;
+pub mod index_state;
+pub mod models;
+pub mod sentence_embedder;
+
+pub use models::{CategorySearchResult, SearchResult};
+
+/// Number of titles embedded and upserted to Qdrant per committed batch.
+const INJEST_BATCH_SIZE: usize = 256;
+
+/// Dimensionality of the sentence-embedding vectors stored in the LanceDB
+/// `category` table (`all-MiniLM-L6-v2`'s output size). The LanceDB schema
+/// needs this fixed up front (`category_schema`, used by `init_db` before
+/// any embedder call happens), so it can't be derived from the embedder at
+/// table-creation time - instead `build_embedding_column` checks every
+/// embedding batch against it before writing, so a model swapped in behind
+/// `EMBEDDING_SERVER` with a different output size fails loudly at ingest
+/// time instead of silently truncating/corrupting the stored vectors.
+const CATEGORY_EMBEDDING_DIM: i32 = 384;
+
+/// Categories embedded and written to LanceDB per committed `RecordBatch`.
+const LANCEDB_BATCH_SIZE: usize = 256;
+
+/// Loads a wiki's `(page_id, title)` pairs from `{DATA_DIR}/{wiki}/articles.parquet`,
+/// the same per-wiki data layout `GraphBuilder` reads for the pageview graph.
+///
+/// Sorted by `page_id` before returning: `injest`'s resume cursor is a
+/// watermark over `page_id`, which only correctly skips already-committed
+/// rows and retries the rest if rows are monotonically increasing in
+/// `page_id` - the raw parquet row order makes no such guarantee.
+fn load_articles(wiki: &str) -> Result<Vec<(u32, String)>, Box<dyn Error>> {
+    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let path = format!("{}/{}/articles.parquet", data_dir, wiki);
+    let df = LazyFrame::scan_parquet(PlPath::Local(Arc::from(FsPath::new(&path))), Default::default())?
+        .sort(["page_id"], SortMultipleOptions::default())
+        .collect()?;
+
+    let ids = df.column("page_id")?.u32()?;
+    let titles = df.column("title")?.str()?;
+
+    Ok(ids
+        .into_iter()
+        .zip(titles.into_iter())
+        .filter_map(|(id, title)| match (id, title) {
+            (Some(id), Some(title)) => Some((id, title.to_string())),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Loads a wiki's `(page_id, title)` pairs from `{DATA_DIR}/{wiki}/categories.parquet`,
+/// the same per-wiki layout `load_articles` reads for articles.
+fn load_categories(wiki: &str) -> Result<Vec<(u32, String)>, Box<dyn Error>> {
+    let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let path = format!("{}/{}/categories.parquet", data_dir, wiki);
+    let df = LazyFrame::scan_parquet(PlPath::Local(Arc::from(FsPath::new(&path))), Default::default())?
+        .collect()?;
+
+    let ids = df.column("page_id")?.u32()?;
+    let titles = df.column("title")?.str()?;
+
+    Ok(ids
+        .into_iter()
+        .zip(titles.into_iter())
+        .filter_map(|(id, title)| match (id, title) {
+            (Some(id), Some(title)) => Some((id, title.to_string())),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Builds the `embedding` column for a batch of category vectors, checking
+/// every vector is exactly [`CATEGORY_EMBEDDING_DIM`] wide first. Skipping
+/// this check and handing a mismatched `flat_values` length straight to
+/// `FixedSizeListArray::new` either panics (too short/long overall) or
+/// silently reinterprets the flat buffer as differently-shaped rows
+/// (if the mismatch happens to still divide evenly), so callers get a clear
+/// error instead - e.g. if `EMBEDDING_SERVER` is pointed at a model whose
+/// output size doesn't match what `category_schema` was created with.
+fn build_embedding_column(vectors: &[Vec<f32>]) -> Result<arrow_array::FixedSizeListArray, Box<dyn Error>> {
+    if let Some(bad) = vectors.iter().find(|v| v.len() != CATEGORY_EMBEDDING_DIM as usize) {
+        return Err(format!(
+            "embedder returned a {}-dim vector, but the category table expects {}-dim embeddings",
+            bad.len(),
+            CATEGORY_EMBEDDING_DIM
+        )
+        .into());
+    }
+
+    let flat_values: Vec<f32> = vectors.iter().flatten().copied().collect();
+    Ok(arrow_array::FixedSizeListArray::new(
+        Arc::new(Field::new("item", DataType::Float32, true)),
+        CATEGORY_EMBEDDING_DIM,
+        Arc::new(Float32Array::from(flat_values)),
+        None,
+    ))
+}
+
+/// The `category` LanceDB table's schema: the category's qid, its title,
+/// and its unit-normalized sentence embedding.
+fn category_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("category_qid", DataType::Int32, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "embedding",
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                CATEGORY_EMBEDDING_DIM,
+            ),
+            false,
+        ),
+    ]))
+}
+
+/// Indexes `wiki`'s articles into its Qdrant collection, resuming after the
+/// last durably-committed watermark instead of re-embedding everything on
+/// every run. Each batch of [`INJEST_BATCH_SIZE`] titles is embedded,
+/// upserted to Qdrant, and only then committed to the watermark, so a crash
+/// mid-run leaves the watermark at the last point actually persisted in
+/// Qdrant rather than ahead of it.
+pub async fn injest(client: &Qdrant, wiki: String) -> Result<(), Box<dyn Error>> {
+    let articles = load_articles(&wiki)?;
+    let total_count = articles.len() as i64;
+
+    let mut state_conn = index_state::open()?;
+    let resume_from = index_state::get_watermark(&state_conn, &wiki)?
+        .map(|watermark| watermark.last_page_id)
+        .unwrap_or(0);
+
+    let pending: Vec<&(u32, String)> = articles
+        .iter()
+        .filter(|(page_id, _)| *page_id as i64 > resume_from)
+        .collect();
+
+    if pending.is_empty() {
+        println!("'{}' is already fully indexed ({} records).", wiki, total_count);
+        return Ok(());
+    }
+
+    let mut embedder = sentence_embedder::SentenceEmbedder::new().await?;
+
+    for batch in pending.chunks(INJEST_BATCH_SIZE) {
+        let titles: Vec<&str> = batch.iter().map(|(_, title)| title.as_str()).collect();
+        let vectors = embedder.encode_batch(&titles).await?;
+
+        let points: Vec<PointStruct> = batch
+            .iter()
+            .zip(vectors.into_iter())
+            .map(|((page_id, title), vector)| {
+                let mut payload = HashMap::new();
+                payload.insert(
+                    "page_id".to_string(),
+                    Value {
+                        kind: Some(Kind::IntegerValue(*page_id as i64)),
+                    },
+                );
+                payload.insert(
+                    "page_title".to_string(),
+                    Value {
+                        kind: Some(Kind::StringValue(title.clone())),
+                    },
+                );
+                PointStruct::new(*page_id as u64, vector, payload)
+            })
+            .collect();
+
+        client
+            .upsert_points(UpsertPointsBuilder::new(wiki.clone(), points))
+            .await?;
+
+        let last_page_id = batch.last().map(|(id, _)| *id as i64).unwrap();
+        index_state::commit_batch(
+            &mut state_conn,
+            &wiki,
+            last_page_id,
+            batch.len() as i64,
+            total_count,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Connects to the Qdrant server pointed to by `QDRANT_URL`.
+pub async fn get_connection() -> Result<Qdrant, Box<dyn std::error::Error>> {
+    let qdrant_url = std::env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6334".to_string());
+    Ok(Qdrant::from_url(&qdrant_url).build()?)
+}
+
+/// Embeds every category in `wiki`'s `categories.parquet` and writes
+/// `(category_qid, title, embedding)` rows into the `category` LanceDB
+/// table, [`LANCEDB_BATCH_SIZE`] at a time so a crash mid-run only loses
+/// the batch in flight rather than the whole ingestion.
+pub async fn ingest_categories(db: &Connection, wiki: &str) -> Result<(), Box<dyn Error>> {
+    let categories = load_categories(wiki)?;
+    let table = db.open_table("category").execute().await?;
+    let mut embedder = sentence_embedder::SentenceEmbedder::new().await?;
+
+    for batch in categories.chunks(LANCEDB_BATCH_SIZE) {
+        let titles: Vec<&str> = batch.iter().map(|(_, title)| title.as_str()).collect();
+        let vectors = embedder.encode_batch(&titles).await?;
+
+        let qids = Int32Array::from(batch.iter().map(|(qid, _)| *qid as i32).collect::<Vec<_>>());
+        let titles_array = StringArray::from(titles.clone());
+        let embeddings = build_embedding_column(&vectors)?;
+
+        let record_batch = RecordBatch::try_new(
+            category_schema(),
+            vec![Arc::new(qids), Arc::new(titles_array), Arc::new(embeddings)],
+        )?;
+
+        let batch_iter = RecordBatchIterator::new(vec![Ok(record_batch)], category_schema());
+        table.add(Box::new(batch_iter)).execute().await?;
+    }
+
+    Ok(())
+}
+
+/// Builds (or rebuilds) an IVF-PQ approximate-nearest-neighbor index over
+/// the `category` table's `embedding` column: vectors are partitioned into
+/// Voronoi cells via k-means, then residuals within each cell are
+/// product-quantized, so `lancedb_search` can prune most of the table
+/// instead of scanning every row.
+pub async fn build_category_index(db: &Connection) -> Result<(), Box<dyn Error>> {
+    let table = db.open_table("category").execute().await?;
+    table
+        .create_index(&["embedding"], Index::IvfPq(IvfPqIndexBuilder::default()))
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Returns the `k` categories whose embeddings are nearest to `query_vector`
+/// in the `category` LanceDB table, nearest first.
+async fn lancedb_search(
     db: Connection,
-    query: String,
+    query_vector: Vec<f32>,
     k: i32,
-) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error>> {
-    let table_name = "category";
-
-    let table = db.open_table(table_name).execute().await.unwrap();
+) -> Result<Vec<CategorySearchResult>, Box<dyn std::error::Error>> {
+    let table = db.open_table("category").execute().await?;
 
-    let stream: std::pin::Pin<Box<dyn RecordBatchStream + Send + 'static>> = table
+    let mut stream: std::pin::Pin<Box<dyn RecordBatchStream + Send + 'static>> = table
         .query()
-        .limit(2)
-        .nearest_to(&[1.0; 128])?
+        .nearest_to(query_vector.as_slice())?
+        .limit(k as usize)
         .execute()
-        .await
-        .unwrap();
+        .await?;
+
+    let mut results = Vec::new();
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+
+        let qids = batch
+            .column_by_name("category_qid")
+            .ok_or("missing category_qid column")?
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .ok_or("category_qid column has unexpected type")?;
+        let titles = batch
+            .column_by_name("title")
+            .ok_or("missing title column")?
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or("title column has unexpected type")?;
+        let distances = batch
+            .column_by_name("_distance")
+            .ok_or("missing _distance column")?
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .ok_or("_distance column has unexpected type")?;
+
+        for row in 0..batch.num_rows() {
+            results.push(CategorySearchResult {
+                category_qid: qids.value(row) as u32,
+                title: titles.value(row).to_string(),
+                distance: distances.value(row),
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+/// Embeds `query` and returns the `n` nearest article vectors from the
+/// `wiki`'s Qdrant collection, scored by cosine similarity.
+pub async fn search(
+    query: String,
+    wiki: String,
+    n: u64,
+) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
+    let mut embedder = sentence_embedder::SentenceEmbedder::new().await?;
+    let query_vector = embedder.encode(&query).await?;
+
+    let client = get_connection().await?;
+    let response = client
+        .search_points(
+            SearchPointsBuilder::new(wiki, query_vector, n)
+                .with_payload(true),
+        )
+        .await?;
 
-    Ok(Vec::new())
+    Ok(response
+        .result
+        .into_iter()
+        .map(|point| SearchResult {
+            score: point.score,
+            payload: point.payload.into_iter().collect(),
+        })
+        .collect())
+}
+
+/// Embeds `query` and returns the `k` nearest categories in `db`'s
+/// `category` table, nearest first.
+pub async fn search_categories(
+    db: Connection,
+    query: String,
+    k: i32,
+) -> Result<Vec<CategorySearchResult>, Box<dyn std::error::Error>> {
+    let mut embedder = sentence_embedder::SentenceEmbedder::new().await?;
+    let query_vector = embedder.encode(&query).await?;
+    lancedb_search(db, query_vector, k).await
 }
 
+/// Connects to the LanceDB database at `uri`, creating the (empty)
+/// `category` table with its full schema if it doesn't already exist.
 pub async fn init_db(uri: String) -> Result<Connection, Box<dyn std::error::Error>> {
     let db = connect(&uri).execute().await?;
     let table_name = "category";
 
-    let schema = Arc::new(Schema::new(vec![
-        Field::new("id", DataType::Int32, false),
-        //Field::new("item", DataType::Utf8, true),
-    ]));
-    let table_name = "category";
-    db.create_empty_table(table_name, schema).execute().await;
+    let exists = db.table_names().execute().await?.iter().any(|name| name == table_name);
+    if !exists {
+        db.create_empty_table(table_name, category_schema()).execute().await?;
+    }
+
     Ok(db)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_embedding_column_round_trips_correctly_sized_vectors() {
+        let vectors = vec![
+            vec![0.0f32; CATEGORY_EMBEDDING_DIM as usize],
+            vec![1.0f32; CATEGORY_EMBEDDING_DIM as usize],
+        ];
+
+        let column = build_embedding_column(&vectors).unwrap();
+        assert_eq!(column.len(), vectors.len());
+        assert_eq!(column.value_length(), CATEGORY_EMBEDDING_DIM);
+    }
+
+    #[test]
+    fn build_embedding_column_rejects_mismatched_dimension() {
+        let vectors = vec![vec![0.0f32; CATEGORY_EMBEDDING_DIM as usize - 1]];
+        assert!(build_embedding_column(&vectors).is_err());
+    }
+}
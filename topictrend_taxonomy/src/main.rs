@@ -1,5 +1,5 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use topictrend_taxonomy::{get_connection, injest, search};
 
 /// CLI for TopicTrend Taxonomy
@@ -17,6 +17,11 @@ enum Commands {
         /// Wiki name (e.g., enwiki)
         wiki: String,
     },
+    /// Report how many records are indexed versus pending for a given wiki
+    IndexStatus {
+        /// Wiki name (e.g., enwiki)
+        wiki: String,
+    },
     /// Search for a query in a given wiki
     Search {
         /// Wiki name (e.g., enwiki)
@@ -26,9 +31,38 @@ enum Commands {
         /// Number of results
         #[clap(default_value_t = 10u64)]
         n: u64,
+        /// Output format
+        #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 }
 
+/// Output format for the `search` subcommand.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+}
+
+/// Structured view of a [`SearchResult`] for `--format json`/`--format ndjson`.
+#[derive(serde::Serialize)]
+struct OutputItem {
+    id: Option<u32>,
+    name: Option<String>,
+    score: f32,
+}
+
+impl From<&topictrend_taxonomy::models::SearchResult> for OutputItem {
+    fn from(result: &topictrend_taxonomy::models::SearchResult) -> Self {
+        OutputItem {
+            id: result.page_id(),
+            name: result.page_title(),
+            score: result.score,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Set up better error reporting
@@ -67,24 +101,72 @@ async fn run() -> Result<()> {
             println!("✓ Indexing completed successfully for '{}'", wiki);
         }
 
-        Commands::Search { wiki, query, n } => {
-            println!("Searching in '{}' for: '{}'", wiki, query);
+        Commands::IndexStatus { wiki } => {
+            let state_conn = topictrend_taxonomy::index_state::open()
+                .expect("Failed to open index-state database");
+            let watermark = topictrend_taxonomy::index_state::get_watermark(&state_conn, &wiki)
+                .expect("Failed to read index watermark");
+            match watermark {
+                Some(watermark) => {
+                    let pending = watermark.total_count - watermark.indexed_count;
+                    println!(
+                        "'{}': {} indexed, {} pending, {} total (watermark at page_id {})",
+                        wiki,
+                        watermark.indexed_count,
+                        pending,
+                        watermark.total_count,
+                        watermark.last_page_id
+                    );
+                }
+                None => println!("'{}' has not been indexed yet.", wiki),
+            }
+        }
+
+        Commands::Search {
+            wiki,
+            query,
+            n,
+            format,
+        } => {
+            if format == OutputFormat::Text {
+                println!("Searching in '{}' for: '{}'", wiki, query);
+            }
 
             let results = search(query.clone(), wiki.clone(), n)
                 .await
                 .expect(format!("Failed to search in wiki '{}'", wiki).as_str());
 
-            if results.is_empty() {
-                println!("No results found for query: '{}'", query);
-                return Ok(());
-            }
-
-            println!("\n✓ Found {} result(s):\n", results.len());
-
-            for (idx, result) in results.iter().enumerate() {
-                println!("Result {}:", idx + 1);
-                print!("{}", result);
-                println!();
+            match format {
+                OutputFormat::Text => {
+                    if results.is_empty() {
+                        println!("No results found for query: '{}'", query);
+                        return Ok(());
+                    }
+
+                    println!("\n✓ Found {} result(s):\n", results.len());
+
+                    for (idx, result) in results.iter().enumerate() {
+                        println!("Result {}:", idx + 1);
+                        print!("{}", result);
+                        println!();
+                    }
+                }
+                OutputFormat::Json => {
+                    let items: Vec<OutputItem> = results.iter().map(OutputItem::from).collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&items).expect("Failed to serialize results")
+                    );
+                }
+                OutputFormat::Ndjson => {
+                    for result in &results {
+                        let item = OutputItem::from(result);
+                        println!(
+                            "{}",
+                            serde_json::to_string(&item).expect("Failed to serialize result")
+                        );
+                    }
+                }
             }
         }
     }
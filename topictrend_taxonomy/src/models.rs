@@ -13,6 +13,33 @@ pub struct SearchResult {
     pub payload: HashMap<String, Value>,
 }
 
+/// One row of a LanceDB nearest-neighbor category search: the matched
+/// category, its title, and its vector distance to the query (lower is
+/// closer).
+pub struct CategorySearchResult {
+    pub category_qid: u32,
+    pub title: String,
+    pub distance: f32,
+}
+
+impl SearchResult {
+    /// Extracts the `page_id` payload field, if present and integer-typed.
+    pub fn page_id(&self) -> Option<u32> {
+        match self.payload.get("page_id")?.kind.as_ref()? {
+            qdrant_client::qdrant::value::Kind::IntegerValue(val) => Some(*val as u32),
+            _ => None,
+        }
+    }
+
+    /// Extracts the `page_title` payload field, if present and string-typed.
+    pub fn page_title(&self) -> Option<String> {
+        match self.payload.get("page_title")?.kind.as_ref()? {
+            qdrant_client::qdrant::value::Kind::StringValue(val) => Some(val.clone()),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for SearchResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Score: {:.4}", self.score)?;
@@ -1,3 +1,4 @@
+use crate::embedding_cache;
 use tonic::Request;
 
 // Include the generated protobuf code
@@ -8,17 +9,43 @@ pub mod embedding {
 use embedding::embedding_service_client::EmbeddingServiceClient;
 use embedding::{Embedding, EncodeRequest, HealthCheckRequest, SimilarityRequest};
 
+/// Cap on the number of texts sent in a single `encode` RPC.
+const MAX_BATCH_TEXTS: usize = 64;
+
+/// Cap on the total character count of a single `encode` RPC, used alongside
+/// [`MAX_BATCH_TEXTS`] so a handful of very long article bodies can't pack
+/// into one oversized request. There's no local tokenizer here (the actual
+/// tokenization happens server-side), so character count is a cheap proxy
+/// for request size rather than a token-accurate budget.
+const MAX_BATCH_CHARS: usize = 64 * 1024;
+
 pub struct SentenceEmbedder {
     client: EmbeddingServiceClient<tonic::transport::Channel>,
+    /// The embedding server's model name, as reported by `health_check`,
+    /// used to key the persistent cache so a model change can't serve
+    /// vectors computed by a different model under the same text.
+    model_id: String,
+    cache: rusqlite::Connection,
 }
 
 impl SentenceEmbedder {
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let embedding_server = std::env::var("EMBEDDING_SERVER")
             .unwrap_or_else(|_| "http://localhost:50051".to_string());
-        let client = EmbeddingServiceClient::connect(embedding_server).await?;
+        let mut client = EmbeddingServiceClient::connect(embedding_server).await?;
+
+        let health = client
+            .health_check(Request::new(HealthCheckRequest {}))
+            .await?
+            .into_inner();
 
-        Ok(Self { client })
+        let cache = embedding_cache::open()?;
+
+        Ok(Self {
+            client,
+            model_id: health.model_name,
+            cache,
+        })
     }
 
     pub async fn encode(&mut self, text: &str) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
@@ -26,21 +53,83 @@ impl SentenceEmbedder {
         Ok(embeddings.into_iter().next().unwrap())
     }
 
+    /// Encodes `texts`, skipping the embedding server entirely for any text
+    /// whose vector is already in the persistent cache under this model, and
+    /// caching the rest after the RPC returns. Results are returned in the
+    /// same order as `texts` regardless of which were cache hits.
     pub async fn encode_batch(
         &mut self,
         texts: &[&str],
     ) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
-        let texts_owned: Vec<String> = texts.iter().map(|s| s.to_string()).collect();
+        let mut results: Vec<Option<Vec<f32>>> = Vec::with_capacity(texts.len());
+        let mut misses: Vec<&str> = Vec::new();
+
+        for &text in texts {
+            results.push(embedding_cache::get(&self.cache, &self.model_id, text)?);
+            if results.last().unwrap().is_none() {
+                misses.push(text);
+            }
+        }
 
-        let request = EncodeRequest {
-            texts: texts_owned,
-            prompt_name: None,
-        };
+        if !misses.is_empty() {
+            let mut embeddings: Vec<Vec<f32>> = Vec::with_capacity(misses.len());
+
+            for chunk in Self::batch_misses(&misses) {
+                let texts_owned: Vec<String> = chunk.iter().map(|s| s.to_string()).collect();
+                let request = EncodeRequest {
+                    texts: texts_owned,
+                    prompt_name: None,
+                };
+
+                let response = self.client.encode(Request::new(request)).await?;
+                embeddings.extend(
+                    response.into_inner().embeddings.into_iter().map(|e| e.values),
+                );
+            }
+
+            let to_cache: Vec<(&str, Vec<f32>)> = misses
+                .iter()
+                .copied()
+                .zip(embeddings.iter().cloned())
+                .collect();
+            embedding_cache::put_batch(&mut self.cache, &self.model_id, &to_cache)?;
+
+            let mut fresh = embeddings.into_iter();
+            for slot in results.iter_mut() {
+                if slot.is_none() {
+                    *slot = fresh.next();
+                }
+            }
+        }
+
+        Ok(results.into_iter().map(|v| v.unwrap()).collect())
+    }
 
-        let response = self.client.encode(Request::new(request)).await?;
-        let embeddings = response.into_inner().embeddings;
+    /// Splits `misses` into RPC-sized chunks bounded by [`MAX_BATCH_TEXTS`]
+    /// and [`MAX_BATCH_CHARS`], so a large cache-miss run (e.g. a cold cache
+    /// on first ingest) issues several bounded `encode` calls instead of one
+    /// request holding every miss in memory at once.
+    fn batch_misses<'a>(misses: &[&'a str]) -> Vec<Vec<&'a str>> {
+        let mut batches = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        let mut current_chars = 0usize;
+
+        for &text in misses {
+            if !current.is_empty()
+                && (current.len() + 1 > MAX_BATCH_TEXTS
+                    || current_chars + text.len() > MAX_BATCH_CHARS)
+            {
+                batches.push(std::mem::take(&mut current));
+                current_chars = 0;
+            }
+            current_chars += text.len();
+            current.push(text);
+        }
+        if !current.is_empty() {
+            batches.push(current);
+        }
 
-        Ok(embeddings.into_iter().map(|e| e.values).collect())
+        batches
     }
 }
 
@@ -0,0 +1,118 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, post},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use crate::handlers::ApiError;
+use crate::models::{AppState, EngineEntry};
+use crate::services::core::CoreServiceError;
+use topictrend::pageview_engine::PageViewEngine;
+
+#[derive(Serialize)]
+pub struct EngineSummary {
+    pub wiki: String,
+    pub num_articles: usize,
+    pub num_categories: usize,
+    pub num_edges: usize,
+    pub approx_bytes: usize,
+    pub data_version: u64,
+}
+
+/// Lists every wiki currently resident in `state.engines` with its graph
+/// size, so operators can see what the cache holds without grepping logs.
+async fn list_engines(State(state): State<Arc<AppState>>) -> Result<Json<Vec<EngineSummary>>, ApiError> {
+    let engines = Arc::clone(&state.engines);
+
+    let summaries = tokio::task::spawn_blocking(move || {
+        let engines = engines.read().map_err(|_| {
+            CoreServiceError::InternalError("Failed to acquire engines lock".to_string())
+        })?;
+
+        let summaries = engines
+            .iter()
+            .map(|(wiki, entry)| {
+                let engine_lock = entry.engine.read().unwrap();
+                let (num_articles, num_categories) = engine_lock.get_wikigraph().node_counts();
+                let num_edges = engine_lock.get_wikigraph().edge_count();
+                EngineSummary {
+                    wiki: wiki.clone(),
+                    num_articles,
+                    num_categories,
+                    num_edges,
+                    approx_bytes: entry.approx_bytes,
+                    data_version: entry.data_version.load(Ordering::SeqCst),
+                }
+            })
+            .collect();
+
+        Ok::<Vec<EngineSummary>, CoreServiceError>(summaries)
+    })
+    .await
+    .map_err(|_| CoreServiceError::InternalError("Failed to spawn blocking task".to_string()))??;
+
+    Ok(Json(summaries))
+}
+
+/// Rebuilds a single wiki's engine from the on-disk graph off-thread, then
+/// atomically swaps the new `Arc<RwLock<PageViewEngine>>` into the map so
+/// in-flight readers holding the old `Arc` keep reading consistent data
+/// until they finish, rather than blocking on a write lock mid-query.
+async fn reload_engine(
+    State(state): State<Arc<AppState>>,
+    Path(wiki): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let wiki_for_build = wiki.clone();
+    let new_entry = tokio::task::spawn_blocking(move || EngineEntry::new(PageViewEngine::new(&wiki_for_build)))
+        .await
+        .map_err(|_| CoreServiceError::InternalError("Failed to spawn blocking task".to_string()))?;
+
+    let engines = Arc::clone(&state.engines);
+    let wiki_for_swap = wiki.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut engines = engines.write().map_err(|_| {
+            CoreServiceError::InternalError("Failed to acquire engines lock".to_string())
+        })?;
+        engines.insert(wiki_for_swap, new_entry);
+        Ok::<(), CoreServiceError>(())
+    })
+    .await
+    .map_err(|_| CoreServiceError::InternalError("Failed to spawn blocking task".to_string()))??;
+
+    Ok((StatusCode::OK, format!("Reloaded engine for {}", wiki)))
+}
+
+/// Evicts a named wiki's engine from the cache. The `Arc` itself is only
+/// dropped once every in-flight reader holding a clone of it finishes.
+async fn evict_engine(
+    State(state): State<Arc<AppState>>,
+    Path(wiki): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let engines = Arc::clone(&state.engines);
+    let removed = tokio::task::spawn_blocking(move || {
+        let mut engines = engines.write().map_err(|_| {
+            CoreServiceError::InternalError("Failed to acquire engines lock".to_string())
+        })?;
+        Ok::<bool, CoreServiceError>(engines.remove(&wiki).is_some())
+    })
+    .await
+    .map_err(|_| CoreServiceError::InternalError("Failed to spawn blocking task".to_string()))??;
+
+    if removed {
+        Ok((StatusCode::OK, "Evicted"))
+    } else {
+        Err(ApiError::from(CoreServiceError::NotFound))
+    }
+}
+
+pub fn create_admin_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/engines", get(list_engines))
+        .route("/engines/{wiki}/reload", post(reload_engine))
+        .route("/engines/{wiki}", delete(evict_engine))
+}
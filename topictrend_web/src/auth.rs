@@ -0,0 +1,38 @@
+use axum::{
+    extract::Request,
+    http::{StatusCode, header::AUTHORIZATION},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Gates the `/admin` and `/admin/pageviews` routers behind a bearer token
+/// matching `ADMIN_API_KEY`, so engine-reload/eviction and the raw
+/// qid-keyed pageview API aren't reachable by anyone who can reach the
+/// public CORS-open server.
+///
+/// The key is read from the environment on every request rather than
+/// cached at startup, so rotating it only requires restarting the process
+/// with the new value.
+pub async fn require_admin_key(request: Request, next: Next) -> Response {
+    let expected = match std::env::var("ADMIN_API_KEY") {
+        Ok(key) => key,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "ADMIN_API_KEY is not configured",
+            )
+                .into_response();
+        }
+    };
+
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if token == expected => next.run(request).await,
+        _ => (StatusCode::UNAUTHORIZED, "Invalid or missing admin API key").into_response(),
+    }
+}
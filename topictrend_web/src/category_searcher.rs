@@ -1,4 +1,9 @@
-use std::collections::HashSet;
+use fst::automaton::Str;
+use fst::{Automaton as FstAutomaton, IntoStreamer, Map as FstMap, MapBuilder, Streamer};
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use topictrend::page_views::PageViews;
 
 #[derive(Debug, Clone)]
 pub struct SearchConfig {
@@ -11,6 +16,63 @@ pub struct SearchConfig {
     pub prefix_match_weight: i32,    // Weight for prefix matches
     pub substring_match_weight: i32, // Weight for substring matches
     pub fuzzy_match_weight: i32,     // Weight for fuzzy matches
+    pub typo_penalty_weight: i32,    // Subtracted from fuzzy_match_weight per edit distance
+    /// The ranking-rule pipeline, applied in order. Each rule only breaks
+    /// ties left by the previous one. Callers can reorder or drop rules
+    /// (an empty vec falls back to insertion order / alphabetical).
+    pub ranking_rules: Vec<RankingRuleKind>,
+    /// Query token -> additional tokens it should also match (e.g. `"tv"`
+    /// -> `["home", "entertainment"]`). Expanded into the query's token
+    /// set before scoring; empty by default.
+    pub synonyms: HashMap<String, Vec<String>>,
+}
+
+/// A single search result together with enough information to highlight
+/// the match in a UI: a diagnostic additive score (not the value the
+/// ranking pipeline itself sorts by) and the byte positions in `category`
+/// that the match covered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub category: String,
+    pub score: i32,
+    pub positions: Vec<usize>,
+}
+
+/// Identifies one of the built-in [`RankingRule`]s so it can live in a
+/// `Clone + Debug` [`SearchConfig`] instead of a `Box<dyn RankingRule>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankingRuleKind {
+    /// Number of query tokens matched, descending.
+    Words,
+    /// Total Levenshtein edit distance, ascending.
+    Typo,
+    /// Full-string prefix > word-boundary prefix > substring.
+    ExactPrefix,
+    /// Tokens in query order and adjacent to each other, descending.
+    Proximity,
+    /// Daily page views of the matching dense id, descending. A no-op
+    /// unless a popularity source was supplied via [`CategorySearcher::with_popularity`].
+    Popularity,
+}
+
+impl RankingRuleKind {
+    fn as_rule(self) -> &'static dyn RankingRule {
+        match self {
+            RankingRuleKind::Words => &WordsRule,
+            RankingRuleKind::Typo => &TypoRule,
+            RankingRuleKind::ExactPrefix => &ExactPrefixRule,
+            RankingRuleKind::Proximity => &ProximityRule,
+            RankingRuleKind::Popularity => &PopularityRule,
+        }
+    }
+}
+
+/// Page-view-backed tie-breaking data for [`RankingRuleKind::Popularity`]:
+/// a loaded [`PageViews`] dump plus the dense id each category (by index)
+/// corresponds to.
+struct PopularitySource {
+    views: Arc<PageViews>,
+    category_dense_ids: Vec<u32>,
 }
 
 impl Default for SearchConfig {
@@ -25,27 +87,423 @@ impl Default for SearchConfig {
             prefix_match_weight: 100,
             substring_match_weight: 50,
             fuzzy_match_weight: 25,
+            typo_penalty_weight: 8,
+            ranking_rules: vec![
+                RankingRuleKind::Words,
+                RankingRuleKind::Typo,
+                RankingRuleKind::ExactPrefix,
+                RankingRuleKind::Proximity,
+            ],
+            synonyms: HashMap::new(),
+        }
+    }
+}
+
+/// Picks the max edit distance to tolerate based on query length: short
+/// queries are unforgiving (one typo in "abcd" changes meaning too much),
+/// longer queries can absorb more edits before becoming a different word.
+fn max_distance_for_query(query: &str) -> u8 {
+    match query.chars().count() {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Lazily-built Levenshtein DFAs, one per supported max edit distance.
+/// Building a `LevenshteinAutomatonBuilder` does non-trivial work, so we
+/// keep exactly the three we need (0, 1, 2) around for the lifetime of
+/// the searcher instead of rebuilding one per query.
+struct TypoTolerance {
+    builders: [LevenshteinAutomatonBuilder; 3],
+}
+
+impl TypoTolerance {
+    fn new() -> Self {
+        Self {
+            builders: [
+                LevenshteinAutomatonBuilder::new(0, true),
+                LevenshteinAutomatonBuilder::new(1, true),
+                LevenshteinAutomatonBuilder::new(2, true),
+            ],
+        }
+    }
+
+    /// Builds a prefix-enabled DFA for `query` using the builder sized for
+    /// `query`'s length.
+    fn build_dfa(&self, query: &str) -> DFA {
+        let max_distance = max_distance_for_query(query);
+        self.builders[max_distance as usize].build_prefix_dfa(query)
+    }
+}
+
+impl std::fmt::Debug for TypoTolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TypoTolerance").finish()
+    }
+}
+
+/// Adapts a [`DFA`] to [`fst::Automaton`] so a Levenshtein automaton can be
+/// streamed directly against an FST instead of evaluated per-candidate.
+struct LevenshteinFstAutomaton<'a>(&'a DFA);
+
+impl<'a> FstAutomaton for LevenshteinFstAutomaton<'a> {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        !matches!(self.0.distance(*state), Distance::AtLeast(_))
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+/// Builds a sorted FST mapping each unique key to the position of its
+/// postings list in the returned `Vec`, grouping duplicate keys (e.g. two
+/// categories that are identical once lowercased) into the same postings.
+fn build_grouped_fst(mut pairs: Vec<(String, usize)>) -> (FstMap<Vec<u8>>, Vec<Vec<usize>>) {
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut postings: Vec<Vec<usize>> = Vec::new();
+    let mut builder = MapBuilder::new(Vec::new()).expect("in-memory FST builder cannot fail");
+    let mut current_key: Option<&str> = None;
+
+    for (key, idx) in &pairs {
+        if current_key != Some(key.as_str()) {
+            postings.push(Vec::new());
+            builder
+                .insert(key.as_bytes(), (postings.len() - 1) as u64)
+                .expect("keys are inserted in sorted order");
+            current_key = Some(key.as_str());
+        }
+        postings.last_mut().unwrap().push(*idx);
+    }
+
+    let bytes = builder.into_inner().expect("in-memory FST builder cannot fail");
+    let map = FstMap::new(bytes).expect("just-built FST bytes are valid");
+    (map, postings)
+}
+
+/// Sub-linear lookup structure over the category list: a token FST (for
+/// word-boundary prefix, exact-token and fuzzy lookups) and a full-string
+/// FST (for whole-category prefix lookups), each mapping to a postings
+/// list of original category indices. This replaces scanning and
+/// lowercasing every category on every keystroke with streaming just the
+/// FST entries that can match for the prefix/token/fuzzy signals. An
+/// arbitrary mid-word substring match (a query that isn't a prefix of any
+/// token, e.g. "omput" inside "Computer Accessories") has no FST entry to
+/// stream against, so `CategorySearcher::fst_candidates` still falls back
+/// to a linear substring scan over `categories` for that one tier - see
+/// its doc comment - to preserve the pre-FST baseline's recall.
+struct CategoryIndex {
+    token_fst: FstMap<Vec<u8>>,
+    token_postings: Vec<Vec<usize>>,
+    category_fst: FstMap<Vec<u8>>,
+    category_postings: Vec<Vec<usize>>,
+}
+
+impl CategoryIndex {
+    fn build(categories: &[String], category_tokens: &[HashSet<String>]) -> Self {
+        let token_pairs: Vec<(String, usize)> = category_tokens
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, tokens)| tokens.iter().map(move |token| (token.clone(), idx)))
+            .collect();
+        let (token_fst, token_postings) = build_grouped_fst(token_pairs);
+
+        let category_pairs: Vec<(String, usize)> = categories
+            .iter()
+            .enumerate()
+            .map(|(idx, category)| (category.to_lowercase(), idx))
+            .collect();
+        let (category_fst, category_postings) = build_grouped_fst(category_pairs);
+
+        Self {
+            token_fst,
+            token_postings,
+            category_fst,
+            category_postings,
+        }
+    }
+
+    fn stream_into<A: FstAutomaton>(
+        fst_map: &FstMap<Vec<u8>>,
+        postings: &[Vec<usize>],
+        automaton: A,
+        out: &mut Vec<usize>,
+    ) {
+        let mut stream = fst_map.search(automaton).into_stream();
+        while let Some((_key, value)) = stream.next() {
+            out.extend(postings[value as usize].iter().copied());
+        }
+    }
+
+    /// Category indices whose full lowercased string starts with `prefix`.
+    fn category_prefix(&self, prefix: &str, out: &mut Vec<usize>) {
+        Self::stream_into(
+            &self.category_fst,
+            &self.category_postings,
+            Str::new(prefix).starts_with(),
+            out,
+        );
+    }
+
+    /// Category indices with at least one token starting with `prefix`.
+    fn token_prefix(&self, prefix: &str, out: &mut Vec<usize>) {
+        Self::stream_into(
+            &self.token_fst,
+            &self.token_postings,
+            Str::new(prefix).starts_with(),
+            out,
+        );
+    }
+
+    /// Category indices with a token exactly equal to `token`.
+    fn token_exact(&self, token: &str, out: &mut Vec<usize>) {
+        if let Some(value) = self.token_fst.get(token) {
+            out.extend(self.token_postings[value as usize].iter().copied());
         }
     }
+
+    /// Category indices with a token within `dfa`'s tolerated edit distance.
+    fn token_fuzzy(&self, dfa: &DFA, out: &mut Vec<usize>) {
+        Self::stream_into(
+            &self.token_fst,
+            &self.token_postings,
+            LevenshteinFstAutomaton(dfa),
+            out,
+        );
+    }
+}
+
+/// Per-query state shared by every ranking rule, computed once per
+/// `search()` call instead of per-candidate.
+struct QueryContext {
+    query_lower: String,
+    query_tokens: HashSet<String>,
+    ordered_query_tokens: Vec<String>,
+    fuzzy_dfa: Option<DFA>,
+}
+
+/// A single ranking criterion in the search pipeline.
+///
+/// Each rule only needs to break ties *within* the groups handed to it by
+/// the previous rule: it partitions `candidates` into ordered buckets
+/// (best bucket first), and candidates that tie on this rule's criterion
+/// stay together for the next rule to resolve. This mirrors how engines
+/// like Meilisearch separate ranking into composable criteria instead of
+/// summing one weighted score.
+trait RankingRule: Send + Sync {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>>;
+}
+
+/// Number of query tokens a category matches, descending.
+struct WordsRule;
+impl RankingRule for WordsRule {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>> {
+        if ctx.query_tokens.is_empty() {
+            return vec![candidates.to_vec()];
+        }
+
+        let mut by_count: std::collections::BTreeMap<usize, Vec<usize>> =
+            std::collections::BTreeMap::new();
+        for &idx in candidates {
+            let matches = searcher.category_tokens[idx]
+                .intersection(&ctx.query_tokens)
+                .count();
+            by_count.entry(matches).or_default().push(idx);
+        }
+
+        by_count.into_values().rev().collect()
+    }
+}
+
+/// Total Levenshtein edit distance against the query tokens, ascending
+/// (candidates outside the typo tolerance sort last).
+struct TypoRule;
+impl RankingRule for TypoRule {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>> {
+        let Some(dfa) = &ctx.fuzzy_dfa else {
+            return vec![candidates.to_vec()];
+        };
+
+        let mut by_distance: std::collections::BTreeMap<u32, Vec<usize>> =
+            std::collections::BTreeMap::new();
+        for &idx in candidates {
+            let distance = match searcher.fuzzy_match_score(&searcher.category_tokens[idx], dfa) {
+                Some((_, distance)) => distance as u32,
+                None => u32::MAX, // No token within tolerance: rank last
+            };
+            by_distance.entry(distance).or_default().push(idx);
+        }
+
+        by_distance.into_values().collect()
+    }
+}
+
+/// Full-string prefix beats word-boundary prefix beats substring beats
+/// neither.
+struct ExactPrefixRule;
+impl RankingRule for ExactPrefixRule {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>> {
+        let mut tiers: [Vec<usize>; 4] = Default::default();
+
+        for &idx in candidates {
+            let category_lower = searcher.categories[idx].to_lowercase();
+            let tier = if category_lower.starts_with(&ctx.query_lower) {
+                0
+            } else if category_lower
+                .split(&[' ', '-', '_'][..])
+                .any(|word| word.starts_with(&ctx.query_lower))
+            {
+                1
+            } else if category_lower.contains(&ctx.query_lower) {
+                2
+            } else {
+                3
+            };
+            tiers[tier].push(idx);
+        }
+
+        tiers.into_iter().filter(|bucket| !bucket.is_empty()).collect()
+    }
+}
+
+/// Rewards tokens that appear in the same order as the query, and more so
+/// when they're adjacent, descending.
+struct ProximityRule;
+impl RankingRule for ProximityRule {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>> {
+        if ctx.ordered_query_tokens.is_empty() {
+            return vec![candidates.to_vec()];
+        }
+
+        let mut by_score: std::collections::BTreeMap<i32, Vec<usize>> =
+            std::collections::BTreeMap::new();
+        for &idx in candidates {
+            let category_lower = searcher.categories[idx].to_lowercase();
+            let score = proximity_score(&category_lower, &ctx.ordered_query_tokens);
+            by_score.entry(score).or_default().push(idx);
+        }
+
+        by_score.into_values().rev().collect()
+    }
+}
+
+/// Daily view count of the category's dense id, descending. Leaves
+/// candidates untouched (single bucket) when no popularity source is
+/// configured, so the searcher stays usable without one.
+struct PopularityRule;
+impl RankingRule for PopularityRule {
+    fn buckets(
+        &self,
+        searcher: &CategorySearcher,
+        _ctx: &QueryContext,
+        candidates: &[usize],
+    ) -> Vec<Vec<usize>> {
+        let Some(popularity) = &searcher.popularity else {
+            return vec![candidates.to_vec()];
+        };
+
+        let mut by_views: std::collections::BTreeMap<u32, Vec<usize>> =
+            std::collections::BTreeMap::new();
+        for &idx in candidates {
+            let dense_id = popularity
+                .category_dense_ids
+                .get(idx)
+                .copied()
+                .unwrap_or(u32::MAX);
+            let views = popularity.views.get(dense_id);
+            by_views.entry(views).or_default().push(idx);
+        }
+
+        by_views.into_values().rev().collect()
+    }
+}
+
+/// Positive when query tokens appear in `text` in order; an extra bonus
+/// per pair of tokens that are also adjacent (no other token between them).
+fn proximity_score(text: &str, ordered_tokens: &[String]) -> i32 {
+    let positions: Vec<usize> = ordered_tokens
+        .iter()
+        .filter_map(|token| text.find(token.as_str()))
+        .collect();
+
+    if positions.len() != ordered_tokens.len() {
+        return 0;
+    }
+
+    let mut score = 0;
+    for (pos_pair, token_pair) in positions.windows(2).zip(ordered_tokens.windows(2)) {
+        if pos_pair[0] <= pos_pair[1] {
+            score += 1;
+            let gap = pos_pair[1].saturating_sub(pos_pair[0] + token_pair[0].len());
+            if gap <= 1 {
+                score += 1; // Adjacency bonus
+            }
+        }
+    }
+    score
 }
 
 pub struct CategorySearcher {
     categories: Vec<String>,
     category_tokens: Vec<HashSet<String>>, // Pre-computed tokens for each category
     config: SearchConfig,
+    typo_tolerance: TypoTolerance,
+    popularity: Option<PopularitySource>,
+    index: CategoryIndex,
 }
 
 impl CategorySearcher {
     pub fn new(categories: Vec<String>) -> Self {
-        let category_tokens = categories
+        let category_tokens: Vec<HashSet<String>> = categories
             .iter()
             .map(|cat| Self::tokenize_string(cat))
             .collect();
+        let index = CategoryIndex::build(&categories, &category_tokens);
 
         Self {
             categories,
             category_tokens,
             config: SearchConfig::default(),
+            typo_tolerance: TypoTolerance::new(),
+            popularity: None,
+            index,
         }
     }
 
@@ -54,6 +512,27 @@ impl CategorySearcher {
         self
     }
 
+    /// Wires up a page-view source so [`RankingRuleKind::Popularity`] can
+    /// break ties by descending view count. `category_dense_ids[i]` must be
+    /// the dense id of `categories[i]` passed to [`Self::new`]; a category
+    /// with no known dense id should map to `u32::MAX`. Adds
+    /// `RankingRuleKind::Popularity` to the configured pipeline if it isn't
+    /// already present.
+    pub fn with_popularity(mut self, views: Arc<PageViews>, category_dense_ids: &[u32]) -> Self {
+        self.popularity = Some(PopularitySource {
+            views,
+            category_dense_ids: category_dense_ids.to_vec(),
+        });
+        if !self
+            .config
+            .ranking_rules
+            .contains(&RankingRuleKind::Popularity)
+        {
+            self.config.ranking_rules.push(RankingRuleKind::Popularity);
+        }
+        self
+    }
+
     fn tokenize_string(s: &str) -> HashSet<String> {
         s.to_lowercase()
             .split(&[' ', '-', '_', '&', ',', '.'][..])
@@ -81,93 +560,262 @@ impl CategorySearcher {
         (token_ratio * self.config.token_match_weight as f32) as i32
     }
 
-    fn is_fuzzy_match(&self, name: &str, query: &str) -> bool {
-        if !self.config.enable_fuzzy || query.len() < 2 {
-            return false;
+    /// Runs the category's tokens through the query's Levenshtein DFA and
+    /// returns a fuzzy-match score plus the best (smallest) edit distance
+    /// found, or `None` if no token is within the tolerated distance.
+    fn fuzzy_match_score(
+        &self,
+        category_tokens: &HashSet<String>,
+        dfa: &DFA,
+    ) -> Option<(i32, u8)> {
+        if !self.config.enable_fuzzy {
+            return None;
         }
 
-        let mut name_chars = name.chars();
-        for q_char in query.chars() {
-            if !name_chars.any(|n_char| n_char == q_char) {
-                return false;
+        let mut best_distance: Option<u8> = None;
+        for token in category_tokens {
+            if let Distance::Exact(distance) = dfa.eval(token) {
+                best_distance = Some(best_distance.map_or(distance, |d| d.min(distance)));
             }
         }
-        true
+
+        let distance = best_distance?;
+        let score = self.config.fuzzy_match_weight
+            - (distance as i32 * self.config.typo_penalty_weight);
+        Some((score.max(0), distance))
     }
 
+    fn build_query_context(&self, query: &str) -> QueryContext {
+        let query_lower = query.to_lowercase();
+        let ordered_query_tokens: Vec<String> = query_lower
+            .split(&[' ', '-', '_', '&', ',', '.'][..])
+            .filter(|token| !token.is_empty())
+            .map(|token| token.to_string())
+            .collect();
+        let query_tokens = self.derive_query_tokens(&ordered_query_tokens);
+        let fuzzy_dfa = self
+            .config
+            .enable_fuzzy
+            .then(|| self.typo_tolerance.build_dfa(&query_lower));
+
+        QueryContext {
+            query_lower,
+            query_tokens,
+            ordered_query_tokens,
+            fuzzy_dfa,
+        }
+    }
+
+    /// Expands the raw query tokens with synonyms and two derived forms,
+    /// then lets the existing token-intersection scoring sort out which
+    /// ones actually match a category: *concatenation* (adjacent tokens
+    /// joined, "home app" -> "homeapp") and *splitting* (a token split at
+    /// each interior position, "homeappliances" -> "home" + "appliances").
+    fn derive_query_tokens(&self, ordered_query_tokens: &[String]) -> HashSet<String> {
+        let mut tokens: HashSet<String> = ordered_query_tokens.iter().cloned().collect();
+
+        for token in ordered_query_tokens {
+            if let Some(synonyms) = self.config.synonyms.get(token) {
+                tokens.extend(synonyms.iter().cloned());
+            }
+        }
+
+        for pair in ordered_query_tokens.windows(2) {
+            tokens.insert(format!("{}{}", pair[0], pair[1]));
+        }
+
+        for token in ordered_query_tokens {
+            let chars: Vec<char> = token.chars().collect();
+            for split_at in 1..chars.len() {
+                let (left, right) = chars.split_at(split_at);
+                tokens.insert(left.iter().collect());
+                tokens.insert(right.iter().collect());
+            }
+        }
+
+        tokens
+    }
+
+    /// Ranks categories against `ctx` by running an ordered pipeline of
+    /// [`RankingRule`]s (see [`SearchConfig::ranking_rules`]): each rule
+    /// bucket-sorts the candidates left tied by the previous one, rather
+    /// than summing every signal into a single score. Returns category
+    /// indices in rank order, untruncated.
+    fn rank(&self, ctx: &QueryContext) -> Vec<usize> {
+        let mut groups: Vec<Vec<usize>> = vec![self.fst_candidates(ctx)];
+
+        for rule_kind in &self.config.ranking_rules {
+            let rule = rule_kind.as_rule();
+            let mut next_groups = Vec::with_capacity(groups.len());
+
+            for group in groups {
+                next_groups.extend(rule.buckets(self, ctx, &group));
+            }
+            groups = next_groups;
+
+            // Stop ranking the long tail once we already have max_results
+            // candidates whose order is fully resolved (singleton buckets).
+            let resolved = groups
+                .iter()
+                .take_while(|bucket| bucket.len() == 1)
+                .count();
+            if resolved >= self.config.max_results {
+                break;
+            }
+        }
+
+        groups
+            .into_iter()
+            .flat_map(|mut bucket| {
+                // Any rule that still leaves a tie breaks it alphabetically.
+                bucket.sort_by_key(|&idx| self.categories[idx].to_lowercase());
+                bucket
+            })
+            .collect()
+    }
+
+    /// Ranks categories against `query` (see [`Self::rank`]) and returns
+    /// just the matching category names.
     pub fn search(&self, query: &str) -> Vec<String> {
+        self.search_with_matches(query)
+            .into_iter()
+            .map(|m| m.category)
+            .collect()
+    }
+
+    /// Like [`Self::search`], but for each result also returns a
+    /// diagnostic match score and the byte positions in the category
+    /// string that the match covered, so a caller can render highlighted
+    /// labels (e.g. a fuzzy-finder UI).
+    pub fn search_with_matches(&self, query: &str) -> Vec<SearchMatch> {
         if query.len() < self.config.min_query_length {
             return self
                 .categories
                 .iter()
                 .take(self.config.max_results)
-                .cloned()
+                .map(|category| SearchMatch {
+                    category: category.clone(),
+                    score: 0,
+                    positions: Vec::new(),
+                })
                 .collect();
         }
 
-        let query_lower = query.to_lowercase();
-        let query_tokens = Self::tokenize_string(&query_lower);
+        let ctx = self.build_query_context(query);
 
-        let mut scored_results: Vec<(i32, &String)> = Vec::new();
+        self.rank(&ctx)
+            .into_iter()
+            .take(self.config.max_results)
+            .map(|idx| {
+                let (score, positions) = self.match_score_and_positions(&ctx, idx);
+                SearchMatch {
+                    category: self.categories[idx].clone(),
+                    score,
+                    positions,
+                }
+            })
+            .collect()
+    }
 
-        for (index, category) in self.categories.iter().enumerate() {
-            let category_lower = category.to_lowercase();
-            let mut score = 0;
+    /// Diagnostic score and highlight positions for `index`, computed with
+    /// the same signals the ranking rules use, but as a single additive
+    /// score for display rather than pipeline buckets. Positions are byte
+    /// offsets into the category string: the whole query's span for a
+    /// prefix/substring hit, or each matched token's own span otherwise.
+    fn match_score_and_positions(&self, ctx: &QueryContext, index: usize) -> (i32, Vec<usize>) {
+        let category_lower = self.categories[index].to_lowercase();
+
+        if !ctx.query_lower.is_empty() && category_lower.starts_with(&ctx.query_lower) {
+            return (
+                self.config.prefix_match_weight,
+                (0..ctx.query_lower.len()).collect(),
+            );
+        }
 
-            // 1. Prefix matching (highest weight)
-            if category_lower.starts_with(&query_lower) {
-                score += self.config.prefix_match_weight;
+        if !ctx.query_lower.is_empty() {
+            if let Some(start) = category_lower.find(&ctx.query_lower) {
+                return (
+                    self.config.substring_match_weight,
+                    (start..start + ctx.query_lower.len()).collect(),
+                );
             }
+        }
 
-            // 2. Word boundary matching
-            if category_lower
-                .split(&[' ', '-', '_'][..])
-                .any(|word| word.starts_with(&query_lower))
-            {
-                score += self.config.prefix_match_weight - 10; // Slightly less than full prefix
-            }
+        let mut positions: Vec<usize> = self.category_tokens[index]
+            .iter()
+            .filter(|token| ctx.query_tokens.contains(*token))
+            .filter_map(|token| {
+                category_lower
+                    .find(token.as_str())
+                    .map(|start| start..start + token.len())
+            })
+            .flatten()
+            .collect();
 
-            // 3. Token-based matching
-            let token_score =
-                self.calculate_token_score(&self.category_tokens[index], &query_tokens);
-            score += token_score;
+        if !positions.is_empty() {
+            positions.sort_unstable();
+            positions.dedup();
+            let score = self.calculate_token_score(&self.category_tokens[index], &ctx.query_tokens);
+            return (score, positions);
+        }
 
-            // 4. Substring matching
-            if category_lower.contains(&query_lower) {
-                score += self.config.substring_match_weight;
+        if let Some(dfa) = &ctx.fuzzy_dfa {
+            if let Some((score, _)) = self.fuzzy_match_score(&self.category_tokens[index], dfa) {
+                return (score, Vec::new());
             }
+        }
 
-            // 5. Fuzzy matching (optional)
-            if self.config.enable_fuzzy && self.is_fuzzy_match(&category_lower, &query_lower) {
-                score += self.config.fuzzy_match_weight;
-            }
+        (0, Vec::new())
+    }
 
-            // 6. Bonus for exact token matches (when query matches a complete token)
-            if self.config.enable_token_search {
-                for token in &self.category_tokens[index] {
-                    if token == &query_lower {
-                        score += self.config.token_match_weight + 10; // Extra bonus for exact token match
-                        break;
-                    }
-                }
-            }
+    /// Collects candidate category indices by streaming `ctx`'s signals
+    /// against the FST [`CategoryIndex`] instead of scanning and
+    /// lowercasing every category: full-string prefix, word-boundary
+    /// prefix, exact token membership, and (if enabled) fuzzy token
+    /// matches. This is the sub-linear replacement for the old per-query
+    /// O(N) candidate scan for those signals.
+    ///
+    /// A pure mid-word substring match - the query isn't a prefix of any
+    /// token, e.g. "omput" inside "Computer Accessories" - has no FST
+    /// entry to stream against, since the FSTs are keyed by whole
+    /// categories/tokens and only support prefix/exact/fuzzy lookups on
+    /// those keys. Without a fallback, such a query would silently lose
+    /// the substring tier [`ExactPrefixRule`] still ranks, relative to the
+    /// pre-FST linear scan. So this falls back to exactly that linear
+    /// scan - O(N), same as the whole old pipeline - but only to recover
+    /// this one tier, and only when the FST streams above didn't already
+    /// find the category via a cheaper signal.
+    fn fst_candidates(&self, ctx: &QueryContext) -> Vec<usize> {
+        let mut candidates = Vec::new();
+
+        if !ctx.query_lower.is_empty() {
+            self.index.category_prefix(&ctx.query_lower, &mut candidates);
+            self.index.token_prefix(&ctx.query_lower, &mut candidates);
+        }
 
-            if score > 0 {
-                scored_results.push((score, category));
+        if self.config.enable_token_search {
+            for token in &ctx.query_tokens {
+                self.index.token_exact(token, &mut candidates);
             }
         }
 
-        // Sort by score (descending), then alphabetically
-        scored_results.sort_by(|a, b| {
-            b.0.cmp(&a.0)
-                .then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
-        });
+        if let Some(dfa) = &ctx.fuzzy_dfa {
+            self.index.token_fuzzy(dfa, &mut candidates);
+        }
 
-        scored_results
-            .into_iter()
-            .take(self.config.max_results)
-            .map(|(_, cat)| cat.clone())
-            .collect()
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        if !ctx.query_lower.is_empty() {
+            let already_found: HashSet<usize> = candidates.iter().copied().collect();
+            for (idx, category) in self.categories.iter().enumerate() {
+                if !already_found.contains(&idx) && category.to_lowercase().contains(&ctx.query_lower) {
+                    candidates.push(idx);
+                }
+            }
+        }
+
+        candidates
     }
 
     // Helper method for token-based search only
@@ -378,6 +1026,9 @@ mod tests {
             prefix_match_weight: 100,
             substring_match_weight: 40,
             fuzzy_match_weight: 20,
+            typo_penalty_weight: 8,
+            ranking_rules: SearchConfig::default().ranking_rules,
+            synonyms: HashMap::new(),
         };
 
         let custom_searcher = CategorySearcher::new(categories).with_config(custom_config);
@@ -418,4 +1069,102 @@ mod tests {
         let results = searcher.search_advanced_tokens("computer science");
         assert_eq!(results[0], "Computer Science Books");
     }
+
+    #[test]
+    fn test_typo_tolerant_fuzzy_match() {
+        let categories = vec![
+            "Electronics & Computers".to_string(),
+            "Home Appliances".to_string(),
+        ];
+
+        let searcher = CategorySearcher::new(categories);
+
+        // "compuer" is a one-edit transposition of "computer"
+        let results = searcher.search("compuer");
+        assert!(results.iter().any(|c| c.contains("Computers")));
+
+        // An unrelated short query should not fuzzy-match everything
+        let results = searcher.search("xyz");
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_with_matches_positions() {
+        let categories = vec![
+            "Computer Accessories".to_string(),
+            "Home Appliances".to_string(),
+        ];
+
+        let searcher = CategorySearcher::new(categories);
+
+        let matches = searcher.search_with_matches("comp");
+        assert_eq!(matches[0].category, "Computer Accessories");
+        assert_eq!(matches[0].positions, vec![0, 1, 2, 3]);
+        assert!(matches[0].score > 0);
+
+        // search() stays a thin wrapper that drops the match metadata
+        assert_eq!(
+            searcher.search("comp"),
+            matches.into_iter().map(|m| m.category).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_synonym_matching() {
+        let categories = vec![
+            "Home Entertainment".to_string(),
+            "Books & Media".to_string(),
+        ];
+
+        let mut synonyms = HashMap::new();
+        synonyms.insert("tv".to_string(), vec!["entertainment".to_string()]);
+
+        let config = SearchConfig {
+            synonyms,
+            ..SearchConfig::default()
+        };
+        let searcher = CategorySearcher::new(categories).with_config(config);
+
+        // Synonym: "tv" expands to "entertainment".
+        let results = searcher.search("tv");
+        assert!(results.iter().any(|c| c == "Home Entertainment"));
+    }
+
+    #[test]
+    fn test_concatenation_matching() {
+        let categories = vec!["HomeApp Express".to_string(), "Books & Media".to_string()];
+        let searcher = CategorySearcher::new(categories);
+
+        // Neither "home" nor "app" alone matches the "homeapp" token, but
+        // the adjacent query tokens joined together do.
+        let results = searcher.search("home app");
+        assert!(results.iter().any(|c| c == "HomeApp Express"));
+    }
+
+    #[test]
+    fn test_split_matching() {
+        let categories = vec!["Home Appliances".to_string(), "Books & Media".to_string()];
+        let searcher = CategorySearcher::new(categories);
+
+        // "homeappliances" is tried split into "home" + "appliances",
+        // both of which are tokens of the category.
+        let results = searcher.search("homeappliances");
+        assert!(results.iter().any(|c| c == "Home Appliances"));
+    }
+
+    #[test]
+    fn test_pure_substring_match_still_found() {
+        let categories = vec![
+            "Computer Accessories".to_string(),
+            "Books & Media".to_string(),
+        ];
+        let searcher = CategorySearcher::new(categories);
+
+        // "omput" is a mid-word substring of "Computer Accessories" - not
+        // a prefix of the category or of any of its tokens, so the FST
+        // candidate streams can't find it; only fst_candidates' linear
+        // substring fallback can.
+        let results = searcher.search("omput");
+        assert_eq!(results, vec!["Computer Accessories"]);
+    }
 }
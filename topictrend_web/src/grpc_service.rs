@@ -2,11 +2,46 @@ use crate::models::AppState;
 use crate::services::composite::DeltaService;
 use crate::services::core::{
     ArticleService, CategoryService, CoreServiceError, PageViewService, QidService,
+    SemanticSearchService, SemanticTarget,
 };
 use chrono::NaiveDate;
+use futures_core::Stream;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 
+/// Outstanding-item capacity for the bounded channels backing the
+/// server-streaming endpoints below, so a slow client applies back-pressure
+/// to the draining task instead of it buffering the whole result set in the
+/// channel.
+const STREAM_CHANNEL_CAPACITY: usize = 64;
+
+/// A `tonic` server-streaming response stream of `T`, terminated by a
+/// `Status` on error.
+type GrpcStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Drains an already-computed `items` `Vec` into a freshly spawned task that
+/// feeds a bounded channel, returning the receiving end as a `GrpcStream`.
+/// This chunks the *wire format* - the client gets one `ArticleViews`/
+/// `CategoryDeltaItem`/etc. message at a time and can apply back-pressure
+/// via the bounded channel - but the handler still has to compute and hold
+/// the full `Vec` in memory before the first item is sent, since the
+/// underlying `PageViewService`/`DeltaService` calls aren't incremental.
+/// Making it stream before computation finishes would require those
+/// services to expose an iterator/stream producer instead of a `Vec`.
+fn stream_items<T: Send + 'static>(items: Vec<T>) -> GrpcStream<T> {
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        for item in items {
+            if tx.send(Ok(item)).await.is_err() {
+                break;
+            }
+        }
+    });
+    Box::pin(ReceiverStream::new(rx))
+}
+
 // Include the generated proto code
 pub mod topictrend_proto {
     tonic::include_proto!("topictrend");
@@ -44,6 +79,11 @@ use topictrend_proto::{
     QidsByTitlesRequest,
     QidsByTitlesResponse,
 
+    // Semantic search messages
+    SemanticSearchRequest,
+    SemanticSearchResponse,
+    SemanticSearchResult,
+
     TitleByQidRequest,
     TitleByQidResponse,
     // Metadata messages
@@ -236,6 +276,48 @@ impl TopicTrendService for TopicTrendGrpcService {
         Ok(Response::new(TopArticlesResponse { articles }))
     }
 
+    type StreamTopArticlesStream = GrpcStream<ArticleViews>;
+
+    /// Server-streaming variant of `get_top_articles`: the same result set,
+    /// yielded one `ArticleViews` at a time on the wire instead of one
+    /// `Response` message, so a client requesting a large `limit` can start
+    /// rendering rows before the rest arrive. `get_top_articles` itself is
+    /// still fully computed and buffered as a `Vec` first - see
+    /// `stream_items` - so this does not reduce server-side memory use.
+    async fn stream_top_articles(
+        &self,
+        request: Request<TopArticlesRequest>,
+    ) -> Result<Response<Self::StreamTopArticlesStream>, Status> {
+        let req = request.into_inner();
+
+        let start_date = parse_date(&req.start_date)?;
+        let end_date = parse_date(&req.end_date)?;
+        let depth = req.depth.unwrap_or(0);
+        let limit = req.limit.unwrap_or(10) as usize;
+
+        let articles = PageViewService::get_top_articles(
+            Arc::clone(&self.state),
+            &req.wiki,
+            req.category_qid,
+            start_date,
+            end_date,
+            depth,
+            limit,
+        )
+        .await
+        .map_err(Status::from)?;
+
+        let articles: Vec<ArticleViews> = articles
+            .into_iter()
+            .map(|art| ArticleViews {
+                article_qid: art.article_qid,
+                total_views: art.total_views,
+            })
+            .collect();
+
+        Ok(Response::new(stream_items(articles)))
+    }
+
     // Delta analysis endpoints
     async fn get_category_delta(
         &self,
@@ -285,6 +367,54 @@ impl TopicTrendService for TopicTrendGrpcService {
         }))
     }
 
+    type StreamCategoryDeltaStream = GrpcStream<CategoryDeltaItem>;
+
+    /// Server-streaming variant of `get_category_delta`: yields each
+    /// `CategoryDeltaItem` on the wire one at a time rather than collecting
+    /// the whole thing into one response message. `get_category_delta`
+    /// itself still fully computes and buffers the result `Vec` first - see
+    /// `stream_items` - so this only chunks the wire format, not memory.
+    async fn stream_category_delta(
+        &self,
+        request: Request<CategoryDeltaRequest>,
+    ) -> Result<Response<Self::StreamCategoryDeltaStream>, Status> {
+        let req = request.into_inner();
+
+        let baseline_start = parse_date(&req.baseline_start_date)?;
+        let baseline_end = parse_date(&req.baseline_end_date)?;
+        let impact_start = parse_date(&req.impact_start_date)?;
+        let impact_end = parse_date(&req.impact_end_date)?;
+        let limit = req.limit.unwrap_or(100) as usize;
+        let depth = req.depth.unwrap_or(0);
+
+        let delta_items = DeltaService::get_category_delta(
+            Arc::clone(&self.state),
+            &req.wiki,
+            baseline_start,
+            baseline_end,
+            impact_start,
+            impact_end,
+            limit,
+            depth,
+        )
+        .await
+        .map_err(Status::from)?;
+
+        let categories: Vec<CategoryDeltaItem> = delta_items
+            .into_iter()
+            .map(|item| CategoryDeltaItem {
+                category_qid: item.category_qid,
+                category_title: item.category_title,
+                baseline_views: item.baseline_views,
+                impact_views: item.impact_views,
+                delta_percentage: item.delta_percentage,
+                absolute_delta: item.absolute_delta,
+            })
+            .collect();
+
+        Ok(Response::new(stream_items(categories)))
+    }
+
     async fn get_article_delta(
         &self,
         request: Request<ArticleDeltaRequest>,
@@ -342,6 +472,55 @@ impl TopicTrendService for TopicTrendGrpcService {
         }))
     }
 
+    type StreamArticleDeltaStream = GrpcStream<ArticleDeltaItem>;
+
+    /// Server-streaming variant of `get_article_delta`: yields each
+    /// `ArticleDeltaItem` on the wire one at a time rather than collecting
+    /// the whole thing into one response message. `get_article_delta`
+    /// itself still fully computes and buffers the result `Vec` first - see
+    /// `stream_items` - so this only chunks the wire format, not memory.
+    async fn stream_article_delta(
+        &self,
+        request: Request<ArticleDeltaRequest>,
+    ) -> Result<Response<Self::StreamArticleDeltaStream>, Status> {
+        let req = request.into_inner();
+
+        let baseline_start = parse_date(&req.baseline_start_date)?;
+        let baseline_end = parse_date(&req.baseline_end_date)?;
+        let impact_start = parse_date(&req.impact_start_date)?;
+        let impact_end = parse_date(&req.impact_end_date)?;
+        let limit = req.limit.unwrap_or(100) as usize;
+        let depth = req.depth.unwrap_or(0);
+
+        let delta_items = DeltaService::get_article_delta(
+            Arc::clone(&self.state),
+            &req.wiki,
+            req.category_qid,
+            baseline_start,
+            baseline_end,
+            impact_start,
+            impact_end,
+            limit,
+            depth,
+        )
+        .await
+        .map_err(Status::from)?;
+
+        let articles: Vec<ArticleDeltaItem> = delta_items
+            .into_iter()
+            .map(|item| ArticleDeltaItem {
+                article_qid: item.article_qid,
+                article_title: item.article_title,
+                baseline_views: item.baseline_views,
+                impact_views: item.impact_views,
+                delta_percentage: item.delta_percentage,
+                absolute_delta: item.absolute_delta,
+            })
+            .collect();
+
+        Ok(Response::new(stream_items(articles)))
+    }
+
     // Metadata endpoints
     async fn get_titles_by_qids(
         &self,
@@ -403,6 +582,47 @@ impl TopicTrendService for TopicTrendGrpcService {
         Ok(Response::new(QidByTitleResponse { qid }))
     }
 
+    // Semantic search endpoint
+    async fn semantic_search(
+        &self,
+        request: Request<SemanticSearchRequest>,
+    ) -> Result<Response<SemanticSearchResponse>, Status> {
+        let req = request.into_inner();
+        let top_k = req.top_k.unwrap_or(10) as usize;
+
+        // 0 = category, 1 = article, matching `SemanticSearchTarget` in the proto.
+        let target = match req.target {
+            0 => SemanticTarget::Category,
+            1 => SemanticTarget::Article,
+            other => {
+                return Err(Status::invalid_argument(format!(
+                    "Unknown semantic search target: {}",
+                    other
+                )));
+            }
+        };
+
+        let hybrid = req.hybrid.unwrap_or(false);
+
+        let hits = SemanticSearchService::hybrid_search(
+            Arc::clone(&self.state),
+            &req.wiki,
+            &req.query,
+            target,
+            top_k,
+            hybrid,
+        )
+        .await
+        .map_err(Status::from)?;
+
+        let results: Vec<SemanticSearchResult> = hits
+            .into_iter()
+            .map(|(qid, score)| SemanticSearchResult { qid, score })
+            .collect();
+
+        Ok(Response::new(SemanticSearchResponse { results }))
+    }
+
     // Graph endpoints
     async fn get_child_categories(
         &self,
@@ -8,11 +8,15 @@ use axum_macros::debug_handler;
 use std::sync::Arc;
 
 use crate::models::{
-    AppState, ArticleDeltaParams, ArticleDeltaResponse, ArticleTrendParams, CategoryDeltaParams,
-    CategoryDeltaResponse, CategoryRankResponse, CategoryTrendParams, DailyViews,
-    SubCategoryParams, TopArticle, TopCategoriesParams, TopCategory,
+    AppState, ArticleDeltaParams, ArticleDeltaResponse, ArticleTrendParams, CategoryBatchOpParam,
+    CategoryBatchRequest, CategoryBatchResultResponse, CategoryDeltaParams,
+    CategoryDeltaResponse, CategoryRankResponse, CategorySearchHitResponse, CategorySearchParams,
+    CategorySearchResponse, CategoryTrendParams, DailyViews, ResolveTitleMatchResponse,
+    ResolveTitleParams, ResolveTitleResponse, SubCategoryParams, TopArticle, TopCategoriesParams,
+    TopCategory,
 };
-use crate::services::composite::DeltaService;
+use crate::services::composite::{CategorySearchHit, DeltaService, PageViewsService as CompositePageViewsService};
+use crate::services::core::{CategoryBatchOp, CategoryBatchQuery, CategoryBatchResult, CategoryService};
 use crate::{
     models::{ArticleTrendResponse, CategoryTrendResponse},
     services::PageViewsService,
@@ -82,20 +86,32 @@ impl IntoResponse for ApiError {
     }
 }
 
+pub async fn get_metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
+
 pub async fn get_category_trend_handler(
     Query(params): Query<CategoryTrendParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<CategoryTrendResponse>, ApiError> {
-    let result = PageViewsService::get_category_trend(
-        state,
-        &params.wiki,
-        &params.category,
-        params.category_qid,
-        params.depth,
-        params.start_date,
-        params.end_date,
-    )
-    .await?;
+    let metrics = Arc::clone(&state.metrics);
+    let result = metrics
+        .track_endpoint(
+            "handler::get_category_trend",
+            PageViewsService::get_category_trend(
+                state,
+                &params.wiki,
+                &params.category,
+                params.category_qid,
+                params.depth,
+                params.start_date,
+                params.end_date,
+            ),
+        )
+        .await?;
 
     let daily_views: Vec<DailyViews> = result
         .views
@@ -125,15 +141,20 @@ pub async fn get_article_trend_handler(
     Query(params): Query<ArticleTrendParams>,
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<ArticleTrendResponse>, ApiError> {
-    let result = PageViewsService::get_article_trend(
-        state,
-        &params.wiki,
-        &params.article,
-        params.article_qid,
-        params.start_date,
-        params.end_date,
-    )
-    .await?;
+    let metrics = Arc::clone(&state.metrics);
+    let result = metrics
+        .track_endpoint(
+            "handler::get_article_trend",
+            PageViewsService::get_article_trend(
+                state,
+                &params.wiki,
+                &params.article,
+                params.article_qid,
+                params.start_date,
+                params.end_date,
+            ),
+        )
+        .await?;
 
     let daily_views = result
         .views
@@ -163,6 +184,79 @@ pub async fn get_sub_categories(
     Ok(Json(titles_map))
 }
 
+/// Resolves a mixed batch of category/article lookups for one wiki in a
+/// single engine lock, so a UI can populate a whole category tree panel in
+/// one round trip instead of one request per node.
+pub async fn get_category_batch_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CategoryBatchRequest>,
+) -> Result<Json<Vec<CategoryBatchResultResponse>>, ApiError> {
+    let queries: Vec<CategoryBatchQuery> = request
+        .queries
+        .into_iter()
+        .map(|q| CategoryBatchQuery {
+            category_qid: q.category_qid,
+            op: match q.op {
+                CategoryBatchOpParam::ChildCategories => CategoryBatchOp::ChildCategories,
+                CategoryBatchOpParam::ParentCategories => CategoryBatchOp::ParentCategories,
+                CategoryBatchOpParam::CategoryArticles { depth } => {
+                    CategoryBatchOp::CategoryArticles { depth }
+                }
+                CategoryBatchOpParam::ValidateExists => CategoryBatchOp::ValidateExists,
+            },
+        })
+        .collect();
+
+    let results = CategoryService::get_batch(state, &request.wiki, queries).await?;
+
+    let response = results
+        .into_iter()
+        .map(|result| match result {
+            CategoryBatchResult::Categories(category_qids) => {
+                CategoryBatchResultResponse::Categories { category_qids }
+            }
+            CategoryBatchResult::Articles(article_qids) => {
+                CategoryBatchResultResponse::Articles { article_qids }
+            }
+            CategoryBatchResult::Exists(exists) => CategoryBatchResultResponse::Exists { exists },
+            CategoryBatchResult::Error(err) => CategoryBatchResultResponse::Error {
+                message: format!("{:?}", err),
+            },
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// Semantic search over a wiki's qdrant article embeddings, constrained to
+/// the subtree of `category_qid` so results stay relevant to the category
+/// the caller is browsing.
+pub async fn get_category_search_handler(
+    Query(params): Query<CategorySearchParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<CategorySearchResponse>, ApiError> {
+    let hits: Vec<CategorySearchHit> = CompositePageViewsService::search_in_category(
+        state,
+        &params.wiki,
+        &params.query,
+        params.category_qid,
+        params.depth.unwrap_or(0),
+        params.top_n,
+    )
+    .await?;
+
+    let results = hits
+        .into_iter()
+        .map(|hit| CategorySearchHitResponse {
+            qid: hit.qid,
+            title: hit.title,
+            score: hit.score,
+        })
+        .collect();
+
+    Ok(Json(CategorySearchResponse { results }))
+}
+
 #[debug_handler]
 pub async fn get_top_categories_handler(
     Query(params): Query<TopCategoriesParams>,
@@ -304,3 +398,29 @@ pub async fn get_article_delta_handler(
         impact_period,
     }))
 }
+
+/// "Did you mean" suggestions for a title that didn't resolve to a QID
+/// directly - a client can call this after a 404 from
+/// `/api/pageviews/category` or `/api/pageviews/article` to offer the user
+/// a correction instead of a hard failure.
+pub async fn resolve_title_handler(
+    Query(params): Query<ResolveTitleParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ResolveTitleResponse>, ApiError> {
+    use crate::services::core::QidService;
+
+    let matches = QidService::resolve_title(
+        state,
+        &params.wiki,
+        &params.query,
+        params.limit.unwrap_or(10),
+    )
+    .await?;
+
+    let results = matches
+        .into_iter()
+        .map(|(title, qid, score)| ResolveTitleMatchResponse { title, qid, score })
+        .collect();
+
+    Ok(Json(ResolveTitleResponse { results }))
+}
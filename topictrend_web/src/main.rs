@@ -1,6 +1,11 @@
+mod admin;
+mod auth;
+mod compression;
 mod grpc_service;
 mod handlers;
+mod metrics;
 mod models;
+mod pageview_routes;
 mod services;
 mod wiki;
 
@@ -13,7 +18,7 @@ use axum::{
     Router,
     http::{Method, StatusCode, header::*},
     response::Html,
-    routing::{get, get_service},
+    routing::{get, get_service, post},
 };
 use std::{net::SocketAddr, sync::Arc};
 use tonic::transport::Server;
@@ -55,6 +60,9 @@ async fn run_http_server(
             "/api/list/sub_categories",
             get(handlers::get_sub_categories),
         )
+        .route("/api/batch", post(handlers::get_category_batch_handler))
+        .route("/api/search", get(handlers::get_category_search_handler))
+        .route("/api/resolve-title", get(handlers::resolve_title_handler))
         .route(
             "/api/list/top_categories",
             get(handlers::get_top_categories_handler),
@@ -67,12 +75,24 @@ async fn run_http_server(
             "/api/delta/articles",
             get(handlers::get_article_delta_handler),
         )
+        .route("/metrics", get(handlers::get_metrics_handler))
+        .nest(
+            "/admin",
+            admin::create_admin_router()
+                .layer(axum::middleware::from_fn(auth::require_admin_key)),
+        )
+        .nest(
+            "/admin/pageviews",
+            pageview_routes::create_pageview_router()
+                .layer(axum::middleware::from_fn(auth::require_admin_key)),
+        )
         .with_state(state)
         .layer(cors)
         .layer(SetResponseHeaderLayer::if_not_present(
             CACHE_CONTROL,
             HeaderValue::from_static("public, max-age=3600"),
-        ));
+        ))
+        .layer(compression::compression_layer());
 
     println!("🚀 HTTP Server started successfully on port {}", port);
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
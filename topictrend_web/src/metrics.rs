@@ -0,0 +1,303 @@
+use crate::services::core::CoreServiceError;
+use prometheus::{
+    Encoder, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
+    TextEncoder, histogram_opts, opts,
+};
+use std::time::Instant;
+
+/// Process-wide Prometheus registry and handles for the counters/histograms
+/// tracked across engine builds, lock contention, and service errors.
+/// Threaded through `AppState` so every service method can record against
+/// the same registry that `/metrics` renders.
+pub struct Metrics {
+    registry: Registry,
+    pub engine_builds_total: IntCounterVec,
+    pub engine_build_duration_seconds: HistogramVec,
+    pub engine_lock_wait_seconds: HistogramVec,
+    pub service_errors_total: IntCounterVec,
+    pub qid_batch_size: HistogramVec,
+    pub qid_batch_duration_seconds: HistogramVec,
+    pub loaded_wikis: IntGauge,
+    pub graph_nodes: IntGaugeVec,
+    pub graph_edges: IntGaugeVec,
+    pub request_duration_seconds: HistogramVec,
+    pub request_errors_total: IntCounterVec,
+    pub engine_cache_resident_bytes: IntGauge,
+    pub engine_cache_evictions_total: IntCounter,
+    pub pageview_method_duration_seconds: HistogramVec,
+    pub pageview_result_size: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let engine_builds_total = IntCounterVec::new(
+            opts!(
+                "topictrend_engine_builds_total",
+                "Count of engine lookups by outcome (hit or cold build)."
+            ),
+            &["wiki", "outcome"],
+        )
+        .unwrap();
+
+        let engine_build_duration_seconds = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_engine_build_duration_seconds",
+                "Time to build a PageViewEngine on a cache miss."
+            ),
+            &["wiki"],
+        )
+        .unwrap();
+
+        let engine_lock_wait_seconds = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_engine_lock_wait_seconds",
+                "Time spent waiting to acquire the engine lock."
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        let service_errors_total = IntCounterVec::new(
+            opts!(
+                "topictrend_service_errors_total",
+                "Count of CoreServiceError by variant."
+            ),
+            &["variant"],
+        )
+        .unwrap();
+
+        let qid_batch_size = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_qid_batch_size",
+                "Number of QIDs resolved per get_titles_by_qids call."
+            ),
+            &["wiki"],
+        )
+        .unwrap();
+
+        let qid_batch_duration_seconds = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_qid_batch_duration_seconds",
+                "Latency of get_titles_by_qids calls."
+            ),
+            &["wiki"],
+        )
+        .unwrap();
+
+        let loaded_wikis = IntGauge::new(
+            "topictrend_loaded_wikis",
+            "Number of wikis with a PageViewEngine currently cached in memory.",
+        )
+        .unwrap();
+
+        let graph_nodes = IntGaugeVec::new(
+            opts!(
+                "topictrend_graph_nodes",
+                "Number of article/category nodes in a wiki's graph."
+            ),
+            &["wiki", "kind"],
+        )
+        .unwrap();
+
+        let graph_edges = IntGaugeVec::new(
+            opts!(
+                "topictrend_graph_edges",
+                "Number of category->category edges in a wiki's graph."
+            ),
+            &["wiki"],
+        )
+        .unwrap();
+
+        let request_duration_seconds = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_request_duration_seconds",
+                "Latency of handler/service endpoints."
+            ),
+            &["endpoint"],
+        )
+        .unwrap();
+
+        let request_errors_total = IntCounterVec::new(
+            opts!(
+                "topictrend_request_errors_total",
+                "Count of endpoint calls that returned an error."
+            ),
+            &["endpoint"],
+        )
+        .unwrap();
+
+        let engine_cache_resident_bytes = IntGauge::new(
+            "topictrend_engine_cache_resident_bytes",
+            "Approximate total heap size of all engines currently cached in memory.",
+        )
+        .unwrap();
+
+        let engine_cache_evictions_total = IntCounter::new(
+            "topictrend_engine_cache_evictions_total",
+            "Count of engines evicted from the cache to stay under the memory budget.",
+        )
+        .unwrap();
+
+        let pageview_method_duration_seconds = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_pageview_method_duration_seconds",
+                "Latency of individual PageViewService methods."
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        let pageview_result_size = HistogramVec::new(
+            histogram_opts!(
+                "topictrend_pageview_result_size",
+                "Number of rows returned by a PageViewService method."
+            ),
+            &["method"],
+        )
+        .unwrap();
+
+        registry
+            .register(Box::new(engine_builds_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(engine_build_duration_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(engine_lock_wait_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(service_errors_total.clone()))
+            .unwrap();
+        registry.register(Box::new(qid_batch_size.clone())).unwrap();
+        registry
+            .register(Box::new(qid_batch_duration_seconds.clone()))
+            .unwrap();
+        registry.register(Box::new(loaded_wikis.clone())).unwrap();
+        registry.register(Box::new(graph_nodes.clone())).unwrap();
+        registry.register(Box::new(graph_edges.clone())).unwrap();
+        registry
+            .register(Box::new(request_duration_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(request_errors_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(engine_cache_resident_bytes.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(engine_cache_evictions_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pageview_method_duration_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pageview_result_size.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            engine_builds_total,
+            engine_build_duration_seconds,
+            engine_lock_wait_seconds,
+            service_errors_total,
+            qid_batch_size,
+            qid_batch_duration_seconds,
+            loaded_wikis,
+            graph_nodes,
+            graph_edges,
+            request_duration_seconds,
+            request_errors_total,
+            engine_cache_resident_bytes,
+            engine_cache_evictions_total,
+            pageview_method_duration_seconds,
+            pageview_result_size,
+        }
+    }
+
+    /// Records a `PageViewService` method's latency and the number of rows
+    /// it returned, so operators can tell whether a slow `/metrics` reading
+    /// comes from a handful of large categories or widespread slow queries.
+    pub fn observe_pageview_method(&self, method: &str, since: Instant, result_len: usize) {
+        self.pageview_method_duration_seconds
+            .with_label_values(&[method])
+            .observe(since.elapsed().as_secs_f64());
+        self.pageview_result_size
+            .with_label_values(&[method])
+            .observe(result_len as f64);
+    }
+
+    /// Records how long an engine read-lock acquisition blocked for a given
+    /// `PageViewsService`/`PageViewService` method, starting from `since`.
+    pub fn observe_lock_wait(&self, method: &str, since: Instant) {
+        self.engine_lock_wait_seconds
+            .with_label_values(&[method])
+            .observe(since.elapsed().as_secs_f64());
+    }
+
+    /// Increments the counter for a `CoreServiceError` variant, identified
+    /// by name so new variants don't need new label plumbing.
+    pub fn record_service_error(&self, variant: &str) {
+        self.service_errors_total
+            .with_label_values(&[variant])
+            .inc();
+    }
+
+    /// Records a freshly-built wiki's graph size, e.g. right after
+    /// `EngineService` constructs its `PageViewEngine`.
+    pub fn record_graph_size(&self, wiki: &str, num_articles: usize, num_categories: usize, num_edges: usize) {
+        self.graph_nodes
+            .with_label_values(&[wiki, "article"])
+            .set(num_articles as i64);
+        self.graph_nodes
+            .with_label_values(&[wiki, "category"])
+            .set(num_categories as i64);
+        self.graph_edges.with_label_values(&[wiki]).set(num_edges as i64);
+    }
+
+    /// Times an endpoint, recording its latency and, on error, incrementing
+    /// the matching error counter.
+    pub async fn track_endpoint<F, T, E>(&self, endpoint: &str, fut: F) -> Result<T, E>
+    where
+        F: std::future::Future<Output = Result<T, E>>,
+    {
+        let started = Instant::now();
+        let result = fut.await;
+        self.request_duration_seconds
+            .with_label_values(&[endpoint])
+            .observe(started.elapsed().as_secs_f64());
+        if result.is_err() {
+            self.request_errors_total.with_label_values(&[endpoint]).inc();
+        }
+        result
+    }
+
+    /// Convenience wrapper around [`Self::record_service_error`] that
+    /// derives the label from a `CoreServiceError` itself.
+    pub fn record_core_error(&self, err: &CoreServiceError) {
+        let variant = match err {
+            CoreServiceError::DatabaseError(_) => "database_error",
+            CoreServiceError::EngineError(_) => "engine_error",
+            CoreServiceError::NotFound => "not_found",
+            CoreServiceError::InternalError(_) => "internal_error",
+        };
+        self.record_service_error(variant);
+    }
+
+    /// Renders the registry in Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&metric_families, &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
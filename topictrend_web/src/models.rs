@@ -1,18 +1,78 @@
 use std::{
     collections::HashMap,
-    sync::{Arc, RwLock},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use sqlx::{MySql, Pool};
-use topictrend::pageview_engine::PageViewEngine;
+use tokio::sync::watch;
+use topictrend::pageview_engine::{PageViewEngine, TopCategoriesCacheWorker};
+
+use crate::metrics::Metrics;
+use crate::services::core::{SemanticIndex, SemanticTarget, TitleIndex};
+
+/// A cached engine plus a monotonically-increasing version bumped whenever
+/// new daily pageview data is ingested into it, so long-poll callers can
+/// wait for a change instead of re-requesting on a timer. `approx_bytes` and
+/// `last_used` back the LRU, memory-budgeted eviction in `EngineService`.
+pub struct EngineEntry {
+    pub engine: Arc<RwLock<PageViewEngine>>,
+    pub data_version: Arc<AtomicU64>,
+    pub version_tx: watch::Sender<u64>,
+    pub approx_bytes: usize,
+    pub last_used: Instant,
+    /// Owns this engine's top-categories cache eviction thread; dropping the
+    /// `EngineEntry` (on cache eviction or reload) stops it. Never read
+    /// directly after construction — kept alive purely for its `Drop`.
+    _cache_eviction_worker: TopCategoriesCacheWorker,
+}
+
+/// How often each engine's top-categories cache is swept for expired
+/// entries. Matches the interval the inline "clean up every 10 minutes"
+/// logic used before it was pulled out into `TopCategoriesCacheWorker`.
+const TOP_CATEGORIES_CACHE_SCAN_INTERVAL: Duration = Duration::from_secs(600);
+
+impl EngineEntry {
+    pub fn new(engine: PageViewEngine) -> Self {
+        let (version_tx, _) = watch::channel(0);
+        let approx_bytes = engine.approx_memory_bytes();
+        let cache_eviction_worker =
+            engine.start_top_categories_cache_eviction_worker(TOP_CATEGORIES_CACHE_SCAN_INTERVAL, None);
+        Self {
+            engine: Arc::new(RwLock::new(engine)),
+            data_version: Arc::new(AtomicU64::new(0)),
+            version_tx,
+            approx_bytes,
+            last_used: Instant::now(),
+            _cache_eviction_worker: cache_eviction_worker,
+        }
+    }
+
+    /// Bumps the data version and wakes any long-poll waiters.
+    pub fn bump_version(&self) -> u64 {
+        let new_version = self.data_version.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.version_tx.send(new_version);
+        new_version
+    }
+}
 
 pub struct AppState {
-    pub engines: Arc<RwLock<HashMap<String, Arc<RwLock<PageViewEngine>>>>>,
+    pub engines: Arc<RwLock<HashMap<String, EngineEntry>>>,
     pub db_pools: Arc<RwLock<HashMap<String, Pool<MySql>>>>,
     pub db_username: String,
     pub db_password: String,
+    pub metrics: Arc<Metrics>,
+    /// Lazily-built, per-(wiki, target) semantic vector indexes backing
+    /// [`crate::services::core::SemanticSearchService`].
+    pub semantic_indexes: Arc<RwLock<HashMap<(String, SemanticTarget), Arc<SemanticIndex>>>>,
+    /// Lazily-built, per-wiki fuzzy/prefix title indexes backing
+    /// [`crate::services::core::QidService::resolve_title`].
+    pub title_indexes: Arc<RwLock<HashMap<String, Arc<TitleIndex>>>>,
 }
 
 impl AppState {
@@ -22,9 +82,12 @@ impl AppState {
 
         Self {
             engines: Arc::new(RwLock::new(HashMap::new())),
+            metrics: Arc::new(Metrics::new()),
             db_pools: Arc::new(RwLock::new(HashMap::new())),
             db_username,
             db_password,
+            semantic_indexes: Arc::new(RwLock::new(HashMap::new())),
+            title_indexes: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -60,6 +123,45 @@ pub struct TopCategoriesParams {
     pub top_n: Option<u8>,
 }
 
+#[derive(Deserialize)]
+pub struct CategorySearchParams {
+    pub wiki: String,
+    pub query: String,
+    pub category_qid: u32,
+    pub depth: Option<u8>,
+    pub top_n: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct ResolveTitleParams {
+    pub wiki: String,
+    pub query: String,
+    pub limit: Option<usize>,
+}
+
+// --- Admin pageview API query DTOs ---
+#[derive(Deserialize)]
+pub struct PageViewTrendQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub depth: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct TopCategoriesQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct TopArticlesQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub depth: Option<u32>,
+    pub limit: Option<usize>,
+}
+
 // --- Response DTO ---
 #[derive(Serialize)]
 pub struct TrendResponse {
@@ -86,3 +188,59 @@ pub struct TopCategory {
 pub struct CategoryRankResponse {
     pub categories: Vec<TopCategory>,
 }
+
+#[derive(Serialize)]
+pub struct CategorySearchHitResponse {
+    pub qid: u32,
+    pub title: String,
+    pub score: f32,
+}
+
+#[derive(Serialize)]
+pub struct CategorySearchResponse {
+    pub results: Vec<CategorySearchHitResponse>,
+}
+
+#[derive(Serialize)]
+pub struct ResolveTitleMatchResponse {
+    pub title: String,
+    pub qid: u32,
+    pub score: f32,
+}
+
+#[derive(Serialize)]
+pub struct ResolveTitleResponse {
+    pub results: Vec<ResolveTitleMatchResponse>,
+}
+
+// --- Batch category/article lookup DTOs ---
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum CategoryBatchOpParam {
+    ChildCategories,
+    ParentCategories,
+    CategoryArticles { depth: u32 },
+    ValidateExists,
+}
+
+#[derive(Deserialize)]
+pub struct CategoryBatchQueryParam {
+    pub category_qid: u32,
+    #[serde(flatten)]
+    pub op: CategoryBatchOpParam,
+}
+
+#[derive(Deserialize)]
+pub struct CategoryBatchRequest {
+    pub wiki: String,
+    pub queries: Vec<CategoryBatchQueryParam>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum CategoryBatchResultResponse {
+    Categories { category_qids: Vec<u32> },
+    Articles { article_qids: Vec<u32> },
+    Exists { exists: bool },
+    Error { message: String },
+}
@@ -0,0 +1,113 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    routing::get,
+};
+use chrono::{Duration, Local, NaiveDate};
+use std::sync::Arc;
+
+use crate::handlers::ApiError;
+use crate::models::{
+    AppState, PageViewTrendQuery, TopArticlesQuery, TopCategoriesQuery, TrendResponse,
+};
+use crate::services::core::PageViewService;
+use crate::services::core::pageview_service::{ArticleViews, CategoryViews};
+
+fn resolve_range(start: Option<NaiveDate>, end: Option<NaiveDate>) -> (NaiveDate, NaiveDate) {
+    let end_date = end.unwrap_or_else(|| Local::now().date_naive());
+    let start_date = start.unwrap_or_else(|| end_date - Duration::days(30));
+    (start_date, end_date)
+}
+
+fn to_trend_response(raw: Vec<(NaiveDate, u64)>) -> Vec<TrendResponse> {
+    raw.into_iter()
+        .map(|(date, views)| TrendResponse { date, views })
+        .collect()
+}
+
+async fn get_category_trend(
+    Path((wiki, category_qid)): Path<(String, u32)>,
+    Query(params): Query<PageViewTrendQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<TrendResponse>>, ApiError> {
+    let (start_date, end_date) = resolve_range(params.start, params.end);
+    let depth = params.depth.unwrap_or(0);
+
+    let raw_data = PageViewService::get_category_views(
+        state,
+        &wiki,
+        category_qid,
+        start_date,
+        end_date,
+        depth,
+    )
+    .await?;
+
+    Ok(Json(to_trend_response(raw_data)))
+}
+
+async fn get_article_trend(
+    Path((wiki, article_qid)): Path<(String, u32)>,
+    Query(params): Query<PageViewTrendQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<TrendResponse>>, ApiError> {
+    let (start_date, end_date) = resolve_range(params.start, params.end);
+
+    let raw_data =
+        PageViewService::get_article_views(state, &wiki, article_qid, start_date, end_date)
+            .await?;
+
+    Ok(Json(to_trend_response(raw_data)))
+}
+
+async fn get_top_categories(
+    Path(wiki): Path<String>,
+    Query(params): Query<TopCategoriesQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<CategoryViews>>, ApiError> {
+    let (start_date, end_date) = resolve_range(params.start, params.end);
+    let limit = params.limit.unwrap_or(10);
+
+    let categories =
+        PageViewService::get_top_categories(state, &wiki, start_date, end_date, limit).await?;
+
+    Ok(Json(categories))
+}
+
+async fn get_top_articles(
+    Path((wiki, category_qid)): Path<(String, u32)>,
+    Query(params): Query<TopArticlesQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ArticleViews>>, ApiError> {
+    let (start_date, end_date) = resolve_range(params.start, params.end);
+    let depth = params.depth.unwrap_or(0);
+    let limit = params.limit.unwrap_or(10);
+
+    let articles = PageViewService::get_top_articles(
+        state,
+        &wiki,
+        category_qid,
+        start_date,
+        end_date,
+        depth,
+        limit,
+    )
+    .await?;
+
+    Ok(Json(articles))
+}
+
+/// Routes `PageViewService`'s methods as a standalone REST API, separate
+/// from the title-enriched `/api/pageviews/*` endpoints in `handlers.rs` -
+/// these return the raw qid-keyed `CategoryViews`/`ArticleViews` shapes
+/// directly, for admin tooling and scripts that don't need display titles.
+pub fn create_pageview_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{wiki}/category/{qid}/trend", get(get_category_trend))
+        .route("/{wiki}/article/{qid}/trend", get(get_article_trend))
+        .route("/{wiki}/top-categories", get(get_top_categories))
+        .route(
+            "/{wiki}/category/{qid}/top-articles",
+            get(get_top_articles),
+        )
+}
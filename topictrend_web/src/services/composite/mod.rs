@@ -2,5 +2,6 @@ pub mod pageviews_service;
 
 pub mod delta_service;
 pub use delta_service::DeltaService;
+pub use pageviews_service::CategorySearchHit;
 pub use pageviews_service::PageViewsService;
 pub use pageviews_service::ServiceError;
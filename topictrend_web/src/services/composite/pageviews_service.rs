@@ -1,8 +1,18 @@
 use crate::models::AppState;
-use crate::services::core::{CoreServiceError, PageViewService, QidService};
+use crate::services::core::{CoreServiceError, EngineService, PageViewService, QidService};
 use chrono::NaiveDate;
 use std::collections::HashMap;
 use std::sync::Arc;
+use topictrend_taxonomy::sentence_embedder::SentenceEmbedder;
+
+/// Weight given to semantic relevance vs. view volume in [`PageViewsService::search_trending`].
+const SEMANTIC_ALPHA: f64 = 0.7;
+/// Titles per `encode_batch` gRPC call, to bound request size.
+const ENCODE_BATCH_SIZE: usize = 256;
+/// How many candidates to over-fetch from qdrant per requested result in
+/// [`PageViewsService::search_in_category`], since most nearest neighbors
+/// will fall outside the requested category subtree.
+const CATEGORY_SEARCH_OVERFETCH: u64 = 10;
 
 pub struct PageViewsService;
 
@@ -22,20 +32,33 @@ pub struct CategoryTrendResult {
     pub title: String,
     pub views: Vec<(NaiveDate, u64)>,
     pub top_articles: Vec<ArticleRank>,
+    /// The wiki engine's data version as of this computation, so callers
+    /// can feed it back into `poll_category_trend`.
+    pub data_version: u64,
 }
 
 pub struct ArticleTrendResult {
     pub qid: u32,
     pub title: String,
     pub views: Vec<(NaiveDate, u64)>,
+    pub data_version: u64,
 }
 
+#[derive(Clone)]
 pub struct ArticleRank {
     pub qid: u32,
     pub title: String,
     pub views: u32,
 }
 
+/// A single match from [`PageViewsService::search_in_category`], ranked by
+/// qdrant cosine similarity rather than view volume.
+pub struct CategorySearchHit {
+    pub qid: u32,
+    pub title: String,
+    pub score: f32,
+}
+
 pub struct CategoryRank {
     pub qid: u32,
     pub title: String,
@@ -43,6 +66,38 @@ pub struct CategoryRank {
     pub top_articles: Vec<ArticleRank>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchQueryKind {
+    Category,
+    Article,
+}
+
+/// One entry in a [`PageViewsService::get_trends_batch`] request: a
+/// category or article, identified by title or (if already known) QID.
+pub struct BatchQuery {
+    pub kind: BatchQueryKind,
+    pub name: String,
+    pub qid: Option<u32>,
+    pub depth: Option<u32>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+/// Positionally aligned with the input `Vec<BatchQuery>`.
+pub enum BatchResult {
+    Category(CategoryTrendResult),
+    Article(ArticleTrendResult),
+    Error(ServiceError),
+}
+
+/// Result of [`PageViewsService::poll_category_trend`]: either the data
+/// changed (or was already newer than `last_seen_version`) and the fresh
+/// trend is returned, or `timeout` elapsed with nothing new.
+pub enum PollOutcome {
+    Updated(CategoryTrendResult),
+    NotModified { data_version: u64 },
+}
+
 impl PageViewsService {
     pub async fn get_category_trend(
         state: Arc<AppState>,
@@ -61,9 +116,16 @@ impl PageViewsService {
         let category_qid = if let Some(qid) = category_qid {
             qid
         } else {
-            QidService::get_qid_by_title(Arc::clone(&state), wiki, category, 14).await?
+            QidService::get_qid_by_title(Arc::clone(&state), wiki, category, 14)
+                .await
+                .map_err(|e| {
+                    state.metrics.record_core_error(&e);
+                    e
+                })?
         };
 
+        let data_version = EngineService::current_engine_version(Arc::clone(&state), wiki).await?;
+
         // Get raw pageview data
         let data = PageViewService::get_category_views(
             Arc::clone(&state),
@@ -114,6 +176,7 @@ impl PageViewsService {
             title: category.to_string(),
             views: data,
             top_articles,
+            data_version,
         })
     }
 
@@ -132,15 +195,22 @@ impl PageViewsService {
         let article_qid = if let Some(qid) = article_qid {
             qid
         } else {
-            QidService::get_qid_by_title(Arc::clone(&state), wiki, article, 0).await?
+            QidService::get_qid_by_title(Arc::clone(&state), wiki, article, 0)
+                .await
+                .map_err(|e| {
+                    state.metrics.record_core_error(&e);
+                    e
+                })?
         };
 
+        let data_version = EngineService::current_engine_version(Arc::clone(&state), wiki).await?;
         let data = PageViewService::get_article_views(state, wiki, article_qid, start, end).await?;
 
         Ok(ArticleTrendResult {
             qid: article_qid,
             title: article.to_string(),
             views: data,
+            data_version,
         })
     }
 
@@ -234,4 +304,361 @@ impl PageViewsService {
 
         Ok(titles_map)
     }
+
+    /// Computes every trend in `queries` under a single engine lock and a
+    /// single batched QID round-trip, instead of the per-query locking and
+    /// `get_titles_by_qids` calls that `get_category_trend`/
+    /// `get_article_trend` each do on their own. Results are positionally
+    /// aligned with `queries`; a query whose title can't be resolved to a
+    /// QID becomes a [`BatchResult::Error`] rather than failing the batch.
+    pub async fn get_trends_batch(
+        state: Arc<AppState>,
+        wiki: &str,
+        queries: Vec<BatchQuery>,
+    ) -> Result<Vec<BatchResult>, ServiceError> {
+        let mut category_titles = Vec::new();
+        let mut article_titles = Vec::new();
+        for query in &queries {
+            if query.qid.is_none() {
+                match query.kind {
+                    BatchQueryKind::Category => category_titles.push(query.name.clone()),
+                    BatchQueryKind::Article => article_titles.push(query.name.clone()),
+                }
+            }
+        }
+
+        let category_qids =
+            QidService::get_qids_by_titles(Arc::clone(&state), wiki, category_titles, 14).await?;
+        let article_qids =
+            QidService::get_qids_by_titles(Arc::clone(&state), wiki, article_titles, 0).await?;
+
+        let resolved: Vec<Option<u32>> = queries
+            .iter()
+            .map(|query| {
+                query.qid.or_else(|| match query.kind {
+                    BatchQueryKind::Category => category_qids.get(&query.name).copied(),
+                    BatchQueryKind::Article => article_qids.get(&query.name).copied(),
+                })
+            })
+            .collect();
+
+        enum Raw {
+            Category(u32, Vec<(NaiveDate, u64)>, Vec<topictrend::pageview_engine::ArticleRank>),
+            Article(u32, Vec<(NaiveDate, u64)>),
+            Error(CoreServiceError),
+        }
+
+        let default_start =
+            chrono::Local::now().date_naive() - chrono::Duration::days(30);
+        let default_end = chrono::Local::now().date_naive();
+
+        let data_version = EngineService::current_engine_version(Arc::clone(&state), wiki).await?;
+        let engine = EngineService::get_or_build_engine(Arc::clone(&state), wiki).await?;
+        let raw_results: Vec<Raw> = {
+            let engine_lock = engine.read().map_err(|e| {
+                ServiceError::CoreError(CoreServiceError::InternalError(format!(
+                    "Failed to acquire read lock: {}",
+                    e
+                )))
+            })?;
+
+            queries
+                .iter()
+                .zip(resolved.iter())
+                .map(|(query, qid)| {
+                    let Some(qid) = qid else {
+                        return Raw::Error(CoreServiceError::NotFound);
+                    };
+                    let start = query.start_date.unwrap_or(default_start);
+                    let end = query.end_date.unwrap_or(default_end);
+
+                    match query.kind {
+                        BatchQueryKind::Category => {
+                            let depth = query.depth.unwrap_or(0);
+                            let views = engine_lock.get_category_trend(*qid, depth, start, end);
+                            match engine_lock.get_top_articles_in_category(*qid, start, end, depth, 10)
+                            {
+                                Ok(rank) => Raw::Category(*qid, views, rank.top_articles),
+                                Err(e) => Raw::Error(CoreServiceError::EngineError(format!(
+                                    "Failed to get top articles: {}",
+                                    e
+                                ))),
+                            }
+                        }
+                        BatchQueryKind::Article => {
+                            Raw::Article(*qid, engine_lock.get_article_trend(*qid, start, end))
+                        }
+                    }
+                })
+                .collect()
+        };
+
+        // Collect every article/category QID referenced across the whole
+        // batch into one get_titles_by_qids call.
+        let mut all_qids: Vec<u32> = Vec::new();
+        for raw in &raw_results {
+            match raw {
+                Raw::Category(qid, _, top_articles) => {
+                    all_qids.push(*qid);
+                    all_qids.extend(top_articles.iter().map(|a| a.article_qid));
+                }
+                Raw::Article(qid, _) => all_qids.push(*qid),
+                Raw::Error(_) => {}
+            }
+        }
+        let titles_map = QidService::get_titles_by_qids(Arc::clone(&state), wiki, &all_qids).await?;
+
+        let results = queries
+            .into_iter()
+            .zip(raw_results)
+            .map(|(query, raw)| match raw {
+                Raw::Category(qid, views, top_articles) => {
+                    let top_articles = top_articles
+                        .into_iter()
+                        .map(|art| ArticleRank {
+                            qid: art.article_qid,
+                            title: titles_map
+                                .get(&art.article_qid)
+                                .cloned()
+                                .unwrap_or_else(|| format!("Q{}", art.article_qid)),
+                            views: art.total_views as u32,
+                        })
+                        .collect();
+
+                    BatchResult::Category(CategoryTrendResult {
+                        qid,
+                        title: titles_map.get(&qid).cloned().unwrap_or(query.name),
+                        views,
+                        top_articles,
+                        data_version,
+                    })
+                }
+                Raw::Article(qid, views) => BatchResult::Article(ArticleTrendResult {
+                    qid,
+                    title: titles_map.get(&qid).cloned().unwrap_or(query.name),
+                    views,
+                    data_version,
+                }),
+                Raw::Error(err) => BatchResult::Error(ServiceError::CoreError(err)),
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Returns the current category trend immediately if the wiki's data
+    /// version is newer than `last_seen_version`; otherwise waits (up to
+    /// `timeout`) for the next ingest before recomputing, or reports
+    /// `NotModified` if `timeout` elapses with nothing new. Lets clients
+    /// long-poll for live updates instead of re-requesting on a timer.
+    pub async fn poll_category_trend(
+        state: Arc<AppState>,
+        wiki: &str,
+        category: &str,
+        category_qid: Option<u32>,
+        depth: Option<u32>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        last_seen_version: u64,
+        timeout: std::time::Duration,
+    ) -> Result<PollOutcome, ServiceError> {
+        let (_, mut version_rx) = EngineService::get_engine_version(Arc::clone(&state), wiki).await?;
+
+        if *version_rx.borrow() <= last_seen_version {
+            let _ = tokio::time::timeout(timeout, async {
+                while *version_rx.borrow() <= last_seen_version {
+                    if version_rx.changed().await.is_err() {
+                        break;
+                    }
+                }
+            })
+            .await;
+        }
+
+        let current_version = *version_rx.borrow();
+        if current_version > last_seen_version {
+            let result = Self::get_category_trend(
+                state,
+                wiki,
+                category,
+                category_qid,
+                depth,
+                start_date,
+                end_date,
+            )
+            .await?;
+            Ok(PollOutcome::Updated(result))
+        } else {
+            Ok(PollOutcome::NotModified {
+                data_version: current_version,
+            })
+        }
+    }
+
+    /// Free-text search over trending articles, ranked by a blend of
+    /// semantic relevance to `query` and view volume. Candidates are the
+    /// top articles across the wiki's top categories for the date range.
+    /// Falls back to pure view-ranking if the embedding server is
+    /// unreachable or returns an error.
+    pub async fn search_trending(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+        top_n: Option<u32>,
+    ) -> Result<Vec<ArticleRank>, ServiceError> {
+        let top_n = top_n.unwrap_or(10) as usize;
+
+        let top_categories =
+            Self::get_top_categories(state, wiki, start_date, end_date, Some(20)).await?;
+
+        let mut by_qid: HashMap<u32, ArticleRank> = HashMap::new();
+        for article in top_categories.into_iter().flat_map(|cat| cat.top_articles) {
+            by_qid
+                .entry(article.qid)
+                .and_modify(|existing| {
+                    if article.views > existing.views {
+                        *existing = article.clone();
+                    }
+                })
+                .or_insert(article);
+        }
+        let mut candidates: Vec<ArticleRank> = by_qid.into_values().collect();
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let max_log_views = candidates
+            .iter()
+            .map(|a| ((a.views as f64) + 1.0).ln())
+            .fold(0.0_f64, f64::max)
+            .max(f64::EPSILON);
+
+        let cosine_scores = Self::semantic_similarity(query, &candidates).await;
+
+        let mut ranked: Vec<(f64, ArticleRank)> = match cosine_scores {
+            Some(scores) => candidates
+                .into_iter()
+                .zip(scores)
+                .map(|(article, cosine)| {
+                    let normalized_log_views = ((article.views as f64) + 1.0).ln() / max_log_views;
+                    let score =
+                        SEMANTIC_ALPHA * cosine as f64 + (1.0 - SEMANTIC_ALPHA) * normalized_log_views;
+                    (score, article)
+                })
+                .collect(),
+            None => candidates
+                .into_iter()
+                .map(|article| (article.views as f64, article))
+                .collect(),
+        };
+
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(ranked.into_iter().take(top_n).map(|(_, a)| a).collect())
+    }
+
+    /// Semantic search over a category subtree: queries the wiki's qdrant
+    /// collection for articles nearest to `query`, then keeps only the hits
+    /// that fall within `category_qid`'s subtree (to `depth` levels) per
+    /// [`topictrend::wikigraph::WikiGraph::get_articles_in_category`].
+    /// Results are ordered by qdrant's similarity score.
+    pub async fn search_in_category(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        category_qid: u32,
+        depth: u8,
+        top_n: Option<u32>,
+    ) -> Result<Vec<CategorySearchHit>, ServiceError> {
+        let top_n = top_n.unwrap_or(10) as usize;
+        let overfetch = top_n as u64 * CATEGORY_SEARCH_OVERFETCH;
+
+        let hits = topictrend_taxonomy::search(query.to_string(), wiki.to_string(), overfetch)
+            .await
+            .map_err(|e| {
+                ServiceError::CoreError(CoreServiceError::InternalError(format!(
+                    "Qdrant search failed: {}",
+                    e
+                )))
+            })?;
+
+        let engine = EngineService::get_or_build_engine(Arc::clone(&state), wiki).await?;
+        let matched: Vec<(u32, f32)> = {
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
+            })?;
+            let wikigraph = engine_lock.get_wikigraph();
+            let articles_in_category = wikigraph
+                .get_articles_in_category(category_qid, depth)
+                .map_err(CoreServiceError::EngineError)?;
+
+            hits.iter()
+                .filter_map(|hit| {
+                    let article_qid = hit.page_id()?;
+                    let dense_id = wikigraph.art_original_to_dense.get(article_qid)?;
+                    articles_in_category
+                        .contains(dense_id)
+                        .then_some((article_qid, hit.score))
+                })
+                .take(top_n)
+                .collect()
+        };
+
+        if matched.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let qids: Vec<u32> = matched.iter().map(|(qid, _)| *qid).collect();
+        let titles_map = QidService::get_titles_by_qids(Arc::clone(&state), wiki, &qids).await?;
+
+        Ok(matched
+            .into_iter()
+            .map(|(qid, score)| CategorySearchHit {
+                qid,
+                title: titles_map
+                    .get(&qid)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Q{}", qid)),
+                score,
+            })
+            .collect())
+    }
+
+    /// Cosine similarity between `query` and each candidate's title,
+    /// computed via the taxonomy service's `SentenceEmbedder`. Returns
+    /// `None` (rather than an error) if the embedding server can't be
+    /// reached or a call fails, so callers can degrade to view-ranking.
+    async fn semantic_similarity(query: &str, candidates: &[ArticleRank]) -> Option<Vec<f32>> {
+        let mut embedder = SentenceEmbedder::new().await.ok()?;
+
+        let mut title_embeddings = Vec::with_capacity(candidates.len());
+        for chunk in candidates.chunks(ENCODE_BATCH_SIZE) {
+            let titles: Vec<&str> = chunk.iter().map(|a| a.title.as_str()).collect();
+            let batch = embedder.encode_batch(&titles).await.ok()?;
+            title_embeddings.extend(batch);
+        }
+
+        let query_embedding = l2_normalize(&embedder.encode(query).await.ok()?);
+
+        Some(
+            title_embeddings
+                .iter()
+                .map(|embedding| dot(&query_embedding, &l2_normalize(embedding)))
+                .collect(),
+        )
+    }
+}
+
+fn l2_normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return v.to_vec();
+    }
+    v.iter().map(|x| x / norm).collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
 }
@@ -2,6 +2,27 @@ use super::{CoreServiceError, EngineService};
 use crate::models::AppState;
 use std::sync::Arc;
 
+/// One operation within a `CategoryService::get_batch` request.
+#[derive(Debug, Clone, Copy)]
+pub enum CategoryBatchOp {
+    ChildCategories,
+    ParentCategories,
+    CategoryArticles { depth: u32 },
+    ValidateExists,
+}
+
+pub struct CategoryBatchQuery {
+    pub category_qid: u32,
+    pub op: CategoryBatchOp,
+}
+
+pub enum CategoryBatchResult {
+    Categories(Vec<u32>),
+    Articles(Vec<u32>),
+    Exists(bool),
+    Error(CoreServiceError),
+}
+
 pub struct CategoryService;
 
 impl CategoryService {
@@ -10,22 +31,33 @@ impl CategoryService {
         wiki: &str,
         category_qid: u32,
     ) -> Result<Vec<u32>, CoreServiceError> {
-        let engine = EngineService::get_or_build_engine(state, wiki).await?;
-
-        let category_qids = {
-            let engine_lock = engine.read().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
-            })?;
-
-            engine_lock
-                .get_wikigraph()
-                .get_child_categories(category_qid)
-                .map_err(|e| {
-                    CoreServiceError::EngineError(format!("Failed to get child categories: {}", e))
-                })?
-        };
-
-        Ok(category_qids)
+        let metrics = Arc::clone(&state.metrics);
+        metrics
+            .track_endpoint("category_service::get_child_categories", async {
+                let engine = EngineService::get_or_build_engine(state, wiki).await?;
+
+                let category_qids = {
+                    let engine_lock = engine.read().map_err(|e| {
+                        CoreServiceError::InternalError(format!(
+                            "Failed to acquire read lock: {}",
+                            e
+                        ))
+                    })?;
+
+                    engine_lock
+                        .get_wikigraph()
+                        .get_child_categories(category_qid)
+                        .map_err(|e| {
+                            CoreServiceError::EngineError(format!(
+                                "Failed to get child categories: {}",
+                                e
+                            ))
+                        })?
+                };
+
+                Ok(category_qids)
+            })
+            .await
     }
 
     pub async fn get_parent_categories(
@@ -33,22 +65,33 @@ impl CategoryService {
         wiki: &str,
         category_qid: u32,
     ) -> Result<Vec<u32>, CoreServiceError> {
-        let engine = EngineService::get_or_build_engine(state, wiki).await?;
-
-        let category_qids = {
-            let engine_lock = engine.read().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
-            })?;
-
-            engine_lock
-                .get_wikigraph()
-                .get_parent_categories(category_qid)
-                .map_err(|e| {
-                    CoreServiceError::EngineError(format!("Failed to get parent categories: {}", e))
-                })?
-        };
-
-        Ok(category_qids)
+        let metrics = Arc::clone(&state.metrics);
+        metrics
+            .track_endpoint("category_service::get_parent_categories", async {
+                let engine = EngineService::get_or_build_engine(state, wiki).await?;
+
+                let category_qids = {
+                    let engine_lock = engine.read().map_err(|e| {
+                        CoreServiceError::InternalError(format!(
+                            "Failed to acquire read lock: {}",
+                            e
+                        ))
+                    })?;
+
+                    engine_lock
+                        .get_wikigraph()
+                        .get_parent_categories(category_qid)
+                        .map_err(|e| {
+                            CoreServiceError::EngineError(format!(
+                                "Failed to get parent categories: {}",
+                                e
+                            ))
+                        })?
+                };
+
+                Ok(category_qids)
+            })
+            .await
     }
 
     pub async fn get_category_articles(
@@ -57,25 +100,33 @@ impl CategoryService {
         category_qid: u32,
         depth: u32,
     ) -> Result<Vec<u32>, CoreServiceError> {
-        let engine = EngineService::get_or_build_engine(state, wiki).await?;
-
-        let article_qids = {
-            let engine_lock = engine.read().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
-            })?;
-
-            engine_lock
-                .get_wikigraph()
-                .get_articles_in_category(category_qid, depth)
-                .map_err(|e| {
-                    CoreServiceError::EngineError(format!(
-                        "Failed to get articles in category: {}",
-                        e
-                    ))
-                })?
-        };
-
-        Ok(article_qids)
+        let metrics = Arc::clone(&state.metrics);
+        metrics
+            .track_endpoint("category_service::get_category_articles", async {
+                let engine = EngineService::get_or_build_engine(state, wiki).await?;
+
+                let article_qids = {
+                    let engine_lock = engine.read().map_err(|e| {
+                        CoreServiceError::InternalError(format!(
+                            "Failed to acquire read lock: {}",
+                            e
+                        ))
+                    })?;
+
+                    engine_lock
+                        .get_wikigraph()
+                        .get_articles_in_category(category_qid, depth)
+                        .map_err(|e| {
+                            CoreServiceError::EngineError(format!(
+                                "Failed to get articles in category: {}",
+                                e
+                            ))
+                        })?
+                };
+
+                Ok(article_qids)
+            })
+            .await
     }
 
     pub async fn validate_category_exists(
@@ -83,20 +134,102 @@ impl CategoryService {
         wiki: &str,
         category_qid: u32,
     ) -> Result<bool, CoreServiceError> {
-        let engine = EngineService::get_or_build_engine(state, wiki).await?;
-
-        let exists = {
-            let engine_lock = engine.read().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
-            })?;
-
-            engine_lock
-                .get_wikigraph()
-                .cat_original_to_dense
-                .get(category_qid)
-                .is_some()
-        };
+        let metrics = Arc::clone(&state.metrics);
+        metrics
+            .track_endpoint("category_service::validate_category_exists", async {
+                let engine = EngineService::get_or_build_engine(state, wiki).await?;
+
+                let exists = {
+                    let engine_lock = engine.read().map_err(|e| {
+                        CoreServiceError::InternalError(format!(
+                            "Failed to acquire read lock: {}",
+                            e
+                        ))
+                    })?;
+
+                    engine_lock
+                        .get_wikigraph()
+                        .cat_original_to_dense
+                        .get(category_qid)
+                        .is_some()
+                };
+
+                Ok(exists)
+            })
+            .await
+    }
 
-        Ok(exists)
+    /// Resolves the engine once and takes a single read lock to execute a
+    /// whole batch of category/article lookups, instead of paying the
+    /// `spawn_blocking` + lock acquisition cost per item. Each item's result
+    /// (or error) is reported positionally, so one bad QID doesn't fail the
+    /// rest of the batch.
+    pub async fn get_batch(
+        state: Arc<AppState>,
+        wiki: &str,
+        queries: Vec<CategoryBatchQuery>,
+    ) -> Result<Vec<CategoryBatchResult>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        metrics
+            .track_endpoint("category_service::get_batch", async {
+                let engine = EngineService::get_or_build_engine(state, wiki).await?;
+
+                let results = {
+                    let engine_lock = engine.read().map_err(|e| {
+                        CoreServiceError::InternalError(format!(
+                            "Failed to acquire read lock: {}",
+                            e
+                        ))
+                    })?;
+                    let wikigraph = engine_lock.get_wikigraph();
+
+                    queries
+                        .into_iter()
+                        .map(|query| match query.op {
+                            CategoryBatchOp::ChildCategories => {
+                                match wikigraph.get_child_categories(query.category_qid) {
+                                    Ok(qids) => CategoryBatchResult::Categories(qids),
+                                    Err(e) => CategoryBatchResult::Error(
+                                        CoreServiceError::EngineError(format!(
+                                            "Failed to get child categories: {}",
+                                            e
+                                        )),
+                                    ),
+                                }
+                            }
+                            CategoryBatchOp::ParentCategories => {
+                                match wikigraph.get_parent_categories(query.category_qid) {
+                                    Ok(qids) => CategoryBatchResult::Categories(qids),
+                                    Err(e) => CategoryBatchResult::Error(
+                                        CoreServiceError::EngineError(format!(
+                                            "Failed to get parent categories: {}",
+                                            e
+                                        )),
+                                    ),
+                                }
+                            }
+                            CategoryBatchOp::CategoryArticles { depth } => {
+                                match wikigraph
+                                    .get_articles_in_category(query.category_qid, depth)
+                                {
+                                    Ok(qids) => CategoryBatchResult::Articles(qids),
+                                    Err(e) => CategoryBatchResult::Error(
+                                        CoreServiceError::EngineError(format!(
+                                            "Failed to get articles in category: {}",
+                                            e
+                                        )),
+                                    ),
+                                }
+                            }
+                            CategoryBatchOp::ValidateExists => CategoryBatchResult::Exists(
+                                wikigraph.cat_original_to_dense.get(query.category_qid).is_some(),
+                            ),
+                        })
+                        .collect()
+                };
+
+                Ok(results)
+            })
+            .await
     }
 }
@@ -1,28 +1,208 @@
-use super::CoreServiceError;
-use crate::models::AppState;
+use super::{CoreServiceError, SemanticSearchService, SemanticTarget};
+use crate::models::{AppState, EngineEntry};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tokio::sync::watch;
 use topictrend::pageview_engine::PageViewEngine;
 
+/// Default cache budget when `ENGINE_CACHE_BYTES` isn't set: 4 GiB.
+const DEFAULT_ENGINE_CACHE_BYTES: usize = 4 * 1024 * 1024 * 1024;
+
+fn engine_cache_budget() -> usize {
+    std::env::var("ENGINE_CACHE_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_ENGINE_CACHE_BYTES)
+}
+
+/// Evicts least-recently-used, currently-idle engines (no outstanding
+/// `Arc<RwLock<PageViewEngine>>` held by an in-flight request) until the
+/// cache's approximate resident size is back under `budget`. Returns the
+/// number of engines evicted.
+fn evict_to_budget(engines: &mut HashMap<String, EngineEntry>, budget: usize) -> usize {
+    let mut resident: usize = engines.values().map(|e| e.approx_bytes).sum();
+    let mut evicted = 0;
+
+    while resident > budget {
+        let lru_idle_key = engines
+            .iter()
+            .filter(|(_, entry)| Arc::strong_count(&entry.engine) <= 1)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(wiki, _)| wiki.clone());
+
+        match lru_idle_key {
+            Some(key) => {
+                if let Some(entry) = engines.remove(&key) {
+                    resident = resident.saturating_sub(entry.approx_bytes);
+                    evicted += 1;
+                }
+            }
+            // Every cached engine is currently in use; can't evict further.
+            None => break,
+        }
+    }
+
+    evicted
+}
+
 pub struct EngineService;
 
 impl EngineService {
+    async fn get_or_build_entry_parts(
+        state: Arc<AppState>,
+        wiki: &str,
+    ) -> Result<(Arc<RwLock<PageViewEngine>>, Arc<AtomicU64>, watch::Receiver<u64>), CoreServiceError>
+    {
+        let wiki_owned = wiki.to_string();
+        let metrics = Arc::clone(&state.metrics);
+        let state_for_indexing = Arc::clone(&state);
+
+        let (parts, is_cold) = tokio::task::spawn_blocking(move || {
+            let wiki = wiki_owned;
+            let mut engines = state.engines.write().map_err(|_| {
+                CoreServiceError::InternalError("Failed to acquire engines lock".to_string())
+            })?;
+
+            if let Some(entry) = engines.get_mut(&wiki) {
+                entry.last_used = Instant::now();
+                metrics
+                    .engine_builds_total
+                    .with_label_values(&[&wiki, "hit"])
+                    .inc();
+                Ok((
+                    (
+                        Arc::clone(&entry.engine),
+                        Arc::clone(&entry.data_version),
+                        entry.version_tx.subscribe(),
+                    ),
+                    false,
+                ))
+            } else {
+                let build_started = Instant::now();
+                let entry = EngineEntry::new(PageViewEngine::new(&wiki));
+                metrics
+                    .engine_build_duration_seconds
+                    .with_label_values(&[&wiki])
+                    .observe(build_started.elapsed().as_secs_f64());
+                metrics
+                    .engine_builds_total
+                    .with_label_values(&[&wiki, "cold"])
+                    .inc();
+
+                let (num_articles, num_categories) = entry
+                    .engine
+                    .read()
+                    .unwrap()
+                    .get_wikigraph()
+                    .node_counts();
+                let num_edges = entry.engine.read().unwrap().get_wikigraph().edge_count();
+                metrics.record_graph_size(&wiki, num_articles, num_categories, num_edges);
+
+                let evicted = evict_to_budget(&mut engines, engine_cache_budget());
+                if evicted > 0 {
+                    metrics.engine_cache_evictions_total.inc_by(evicted as u64);
+                }
+
+                let parts = (
+                    Arc::clone(&entry.engine),
+                    Arc::clone(&entry.data_version),
+                    entry.version_tx.subscribe(),
+                );
+                engines.insert(wiki.clone(), entry);
+
+                metrics.loaded_wikis.set(engines.len() as i64);
+                let resident: usize = engines.values().map(|e| e.approx_bytes).sum();
+                metrics.engine_cache_resident_bytes.set(resident as i64);
+
+                Ok((parts, true))
+            }
+        })
+        .await
+        .map_err(|_| CoreServiceError::InternalError("Failed to spawn blocking task".to_string()))??;
+
+        if is_cold {
+            Self::spawn_eager_semantic_indexing(state_for_indexing, wiki);
+        }
+
+        Ok(parts)
+    }
+
+    /// Fired once per wiki right after its engine is cold-built: proactively
+    /// embeds and caches its category/article titles in the background so
+    /// the first real `SemanticSearch` request doesn't pay that cost
+    /// inline. Best-effort — an embedding server outage just means the
+    /// first search request builds the index itself, same as before this
+    /// existed.
+    fn spawn_eager_semantic_indexing(state: Arc<AppState>, wiki: &str) {
+        let wiki = wiki.to_string();
+        tokio::spawn(async move {
+            for target in [SemanticTarget::Category, SemanticTarget::Article] {
+                if let Err(err) =
+                    SemanticSearchService::get_or_build_index(Arc::clone(&state), &wiki, target).await
+                {
+                    eprintln!(
+                        "Eager semantic indexing failed for '{}' ({:?}): {:?}",
+                        wiki, target, err
+                    );
+                }
+            }
+        });
+    }
+
     pub async fn get_or_build_engine(
         state: Arc<AppState>,
         wiki: &str,
     ) -> Result<Arc<RwLock<PageViewEngine>>, CoreServiceError> {
+        let (engine, _, _) = Self::get_or_build_entry_parts(state, wiki).await?;
+        Ok(engine)
+    }
+
+    /// Approximate resident bytes across all cached engines and the total
+    /// number of evictions performed so far, as tracked in `state.metrics`.
+    pub fn cache_stats(state: &Arc<AppState>) -> (i64, i64) {
+        (
+            state.metrics.engine_cache_resident_bytes.get(),
+            state.metrics.engine_cache_evictions_total.get(),
+        )
+    }
+
+    /// Current data version and a receiver that resolves on the next
+    /// ingest, for long-poll style trend updates.
+    pub async fn get_engine_version(
+        state: Arc<AppState>,
+        wiki: &str,
+    ) -> Result<(Arc<AtomicU64>, watch::Receiver<u64>), CoreServiceError> {
+        let (_, version, receiver) = Self::get_or_build_entry_parts(state, wiki).await?;
+        Ok((version, receiver))
+    }
+
+    /// Current data version without subscribing to change notifications.
+    pub async fn current_engine_version(
+        state: Arc<AppState>,
+        wiki: &str,
+    ) -> Result<u64, CoreServiceError> {
+        let (_, version, _) = Self::get_or_build_entry_parts(state, wiki).await?;
+        Ok(version.load(Ordering::SeqCst))
+    }
+
+    /// Bumps a wiki's data version, e.g. after new daily pageview data has
+    /// been ingested into its engine, waking any long-poll waiters.
+    pub async fn bump_engine_version(
+        state: Arc<AppState>,
+        wiki: &str,
+    ) -> Result<u64, CoreServiceError> {
         let wiki = wiki.to_string();
 
         tokio::task::spawn_blocking(move || {
-            let mut engines = state.engines.write().map_err(|_| {
+            let engines = state.engines.read().map_err(|_| {
                 CoreServiceError::InternalError("Failed to acquire engines lock".to_string())
             })?;
 
-            if let Some(engine) = engines.get(&wiki) {
-                Ok(Arc::clone(engine))
-            } else {
-                let new_engine = Arc::new(RwLock::new(PageViewEngine::new(&wiki)));
-                engines.insert(wiki.clone(), Arc::clone(&new_engine));
-                Ok(new_engine)
+            match engines.get(&wiki) {
+                Some(entry) => Ok(entry.bump_version()),
+                None => Err(CoreServiceError::NotFound),
             }
         })
         .await
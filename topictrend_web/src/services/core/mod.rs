@@ -3,12 +3,14 @@ pub mod category_service;
 pub mod engine_service;
 pub mod pageview_service;
 pub mod qid_service;
+pub mod semantic_search_service;
 
 pub use article_service::ArticleService;
-pub use category_service::CategoryService;
+pub use category_service::{CategoryBatchOp, CategoryBatchQuery, CategoryBatchResult, CategoryService};
 pub use engine_service::EngineService;
 pub use pageview_service::PageViewService;
-pub use qid_service::QidService;
+pub use qid_service::{QidService, TitleIndex};
+pub use semantic_search_service::{SemanticIndex, SemanticSearchService, SemanticTarget};
 
 #[derive(Debug)]
 pub enum CoreServiceError {
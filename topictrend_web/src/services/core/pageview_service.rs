@@ -1,15 +1,17 @@
 use super::{CoreServiceError, EngineService};
 use crate::models::AppState;
 use chrono::NaiveDate;
+use serde::Serialize;
 use std::sync::Arc;
+use std::time::Instant;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ArticleViews {
     pub article_qid: u32,
     pub total_views: u64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct CategoryViews {
     pub category_qid: u32,
     pub total_views: u64,
@@ -27,16 +29,21 @@ impl PageViewService {
         end_date: NaiveDate,
         depth: u32,
     ) -> Result<Vec<(NaiveDate, u64)>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        let method_started = Instant::now();
         let engine = EngineService::get_or_build_engine(state, wiki).await?;
 
         let raw_data = {
-            let mut engine_lock = engine.write().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire write lock: {}", e))
+            let lock_wait_started = Instant::now();
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
             })?;
+            metrics.observe_lock_wait("get_category_views", lock_wait_started);
 
             engine_lock.get_category_trend(category_qid, depth, start_date, end_date)
         };
 
+        metrics.observe_pageview_method("get_category_views", method_started, raw_data.len());
         Ok(raw_data)
     }
 
@@ -47,16 +54,21 @@ impl PageViewService {
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<Vec<(NaiveDate, u64)>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        let method_started = Instant::now();
         let engine = EngineService::get_or_build_engine(state, wiki).await?;
 
         let raw_data = {
-            let mut engine_lock = engine.write().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire write lock: {}", e))
+            let lock_wait_started = Instant::now();
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
             })?;
+            metrics.observe_lock_wait("get_article_views", lock_wait_started);
 
             engine_lock.get_article_trend(article_qid, start_date, end_date)
         };
 
+        metrics.observe_pageview_method("get_article_views", method_started, raw_data.len());
         Ok(raw_data)
     }
 
@@ -69,12 +81,16 @@ impl PageViewService {
         depth: u32,
         limit: usize,
     ) -> Result<Vec<ArticleViews>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        let method_started = Instant::now();
         let engine = EngineService::get_or_build_engine(state, wiki).await?;
 
         let top_articles = {
-            let mut engine_lock = engine.write().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire write lock: {}", e))
+            let lock_wait_started = Instant::now();
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
             })?;
+            metrics.observe_lock_wait("get_top_articles", lock_wait_started);
 
             engine_lock
                 .get_top_articles_in_category(category_qid, start_date, end_date, depth, limit)
@@ -92,6 +108,7 @@ impl PageViewService {
             })
             .collect();
 
+        metrics.observe_pageview_method("get_top_articles", method_started, raw_articles.len());
         Ok(raw_articles)
     }
 
@@ -102,12 +119,16 @@ impl PageViewService {
         end_date: NaiveDate,
         limit: usize,
     ) -> Result<Vec<CategoryViews>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        let method_started = Instant::now();
         let engine = EngineService::get_or_build_engine(state, wiki).await?;
 
         let categories = {
-            let mut engine_lock = engine.write().map_err(|e| {
-                CoreServiceError::InternalError(format!("Failed to acquire write lock: {}", e))
+            let lock_wait_started = Instant::now();
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
             })?;
+            metrics.observe_lock_wait("get_top_categories", lock_wait_started);
 
             engine_lock
                 .get_top_categories(start_date, end_date, limit)
@@ -136,6 +157,7 @@ impl PageViewService {
             })
             .collect();
 
+        metrics.observe_pageview_method("get_top_categories", method_started, raw_categories.len());
         Ok(raw_categories)
     }
 }
@@ -1,9 +1,132 @@
-use super::CoreServiceError;
+use super::{CoreServiceError, EngineService};
 use crate::models::AppState;
 use crate::wiki::get_or_create_db_pool;
+use fst::automaton::Str;
+use fst::{Automaton as FstAutomaton, IntoStreamer, Map as FstMap, MapBuilder, Streamer};
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA};
 use sqlx::Row;
+use std::collections::HashSet;
+use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
 
+/// Lowercases and unifies `_`/` ` so `"Foo_Bar"` and `"foo bar"` land on the
+/// same index entry - MediaWiki titles use underscores in storage but users
+/// type spaces.
+fn normalize_title(title: &str) -> String {
+    title.to_lowercase().replace('_', " ")
+}
+
+/// Picks the max edit distance to tolerate based on query length, same
+/// reasoning as [`crate::category_searcher`]'s rule of the same name: short
+/// queries are unforgiving, longer ones can absorb more edits.
+fn max_distance_for_query(query: &str) -> u8 {
+    match query.chars().count() {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Lazily-built Levenshtein DFAs, one per supported max edit distance (see
+/// [`crate::category_searcher::TypoTolerance`], which this mirrors).
+struct TypoTolerance {
+    builders: [LevenshteinAutomatonBuilder; 3],
+}
+
+impl TypoTolerance {
+    fn new() -> Self {
+        Self {
+            builders: [
+                LevenshteinAutomatonBuilder::new(0, true),
+                LevenshteinAutomatonBuilder::new(1, true),
+                LevenshteinAutomatonBuilder::new(2, true),
+            ],
+        }
+    }
+
+    fn build_dfa(&self, query: &str) -> DFA {
+        let max_distance = max_distance_for_query(query);
+        self.builders[max_distance as usize].build_prefix_dfa(query)
+    }
+}
+
+/// Adapts a [`DFA`] to [`fst::Automaton`] so it can be streamed directly
+/// against an FST instead of evaluated per-candidate.
+struct LevenshteinFstAutomaton<'a>(&'a DFA);
+
+impl<'a> FstAutomaton for LevenshteinFstAutomaton<'a> {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        !matches!(self.0.distance(*state), Distance::AtLeast(_))
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+/// A per-wiki in-memory title lookup backing [`QidService::resolve_title`]:
+/// a sorted FST mapping each normalized title to a postings list of
+/// `(title, qid)` pairs (grouping distinct-cased titles that normalize to
+/// the same key), so prefix and fuzzy candidate discovery are sub-linear in
+/// the number of titles instead of scanning every one per query.
+pub struct TitleIndex {
+    fst: FstMap<Vec<u8>>,
+    postings: Vec<Vec<usize>>,
+    entries: Vec<(String, u32)>,
+}
+
+impl TitleIndex {
+    fn build(titled: Vec<(String, u32)>) -> Self {
+        let mut pairs: Vec<(String, usize)> = titled
+            .iter()
+            .enumerate()
+            .map(|(idx, (title, _))| (normalize_title(title), idx))
+            .collect();
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut postings: Vec<Vec<usize>> = Vec::new();
+        let mut builder = MapBuilder::new(Vec::new()).expect("in-memory FST builder cannot fail");
+        let mut current_key: Option<&str> = None;
+
+        for (key, idx) in &pairs {
+            if current_key != Some(key.as_str()) {
+                postings.push(Vec::new());
+                builder
+                    .insert(key.as_bytes(), (postings.len() - 1) as u64)
+                    .expect("keys are inserted in sorted order");
+                current_key = Some(key.as_str());
+            }
+            postings.last_mut().unwrap().push(*idx);
+        }
+
+        let bytes = builder.into_inner().expect("in-memory FST builder cannot fail");
+        let fst = FstMap::new(bytes).expect("just-built FST bytes are valid");
+
+        Self {
+            fst,
+            postings,
+            entries: titled,
+        }
+    }
+
+    fn stream_candidates<A: FstAutomaton>(&self, automaton: A, out: &mut HashSet<usize>) {
+        let mut stream = self.fst.search(automaton).into_stream();
+        while let Some((_key, value)) = stream.next() {
+            out.extend(self.postings[value as usize].iter().copied());
+        }
+    }
+}
+
 pub struct QidService;
 
 impl QidService {
@@ -43,12 +166,20 @@ impl QidService {
         wiki: &str,
         qids: &Vec<u32>,
     ) -> Result<HashMap<u32, String>, CoreServiceError> {
+        let metrics = Arc::clone(&state.metrics);
+        let wiki_label = wiki.to_string();
+        let batch_started = Instant::now();
         let pool = get_or_create_db_pool(state, wiki).await?;
 
         if qids.is_empty() {
             return Ok(HashMap::new());
         }
 
+        metrics
+            .qid_batch_size
+            .with_label_values(&[&wiki_label])
+            .observe(qids.len() as f64);
+
         // Create placeholders for the IN clause
         let placeholders: Vec<String> = qids.iter().map(|_| "?".to_string()).collect();
         let placeholders_str = placeholders.join(",");
@@ -65,6 +196,10 @@ impl QidService {
         }
 
         let rows = query_builder.fetch_all(&pool).await?;
+        metrics
+            .qid_batch_duration_seconds
+            .with_label_values(&[&wiki_label])
+            .observe(batch_started.elapsed().as_secs_f64());
 
         let mut result = HashMap::new();
         for row in rows {
@@ -120,4 +255,227 @@ impl QidService {
 
         Ok(result)
     }
+
+    /// Titles starting with `query` (case-sensitive, SQL `LIKE`-escaped),
+    /// ranked shortest-title-first as a proxy for "closest to an exact
+    /// match", for the keyword side of hybrid search. Returns at most
+    /// `limit` `(title, qid)` pairs, best match first.
+    pub async fn search_titles_by_prefix(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        namespace: i8,
+        limit: usize,
+    ) -> Result<Vec<(String, u32)>, CoreServiceError> {
+        let pool = get_or_create_db_pool(state, wiki).await?;
+
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let like_pattern = format!("{}%", query.replace('%', "\\%").replace('_', "\\_"));
+
+        let rows = sqlx::query(
+            "SELECT p.page_title, w.qid FROM page p
+             JOIN wb_items_per_site w ON p.page_id = w.ips_site_page
+             WHERE p.page_title LIKE ? AND p.page_namespace = ?
+             ORDER BY LENGTH(p.page_title) ASC, p.page_title ASC
+             LIMIT ?",
+        )
+        .bind(like_pattern)
+        .bind(namespace)
+        .bind(limit as i64)
+        .fetch_all(&pool)
+        .await?;
+
+        let mut result = Vec::with_capacity(rows.len());
+        for row in rows {
+            let title_bytes: Vec<u8> = row.try_get("page_title")?;
+            let title = String::from_utf8_lossy(&title_bytes).to_string();
+            let qid: u32 = row.try_get("qid")?;
+            result.push((title, qid));
+        }
+
+        Ok(result)
+    }
+
+    /// Returns `wiki`'s cached fuzzy title index, building it lazily (like
+    /// [`super::SemanticSearchService::get_or_build_index`]) on first
+    /// request: every article qid in the wikigraph is resolved to a title
+    /// via [`Self::get_titles_by_qids`] and indexed, then cached so later
+    /// lookups reuse it instead of re-fetching every title.
+    async fn get_or_build_title_index(
+        state: Arc<AppState>,
+        wiki: &str,
+    ) -> Result<Arc<TitleIndex>, CoreServiceError> {
+        {
+            let indexes = state.title_indexes.read().map_err(|_| {
+                CoreServiceError::InternalError("Failed to acquire title index lock".to_string())
+            })?;
+            if let Some(index) = indexes.get(wiki) {
+                return Ok(Arc::clone(index));
+            }
+        }
+
+        let engine = EngineService::get_or_build_engine(Arc::clone(&state), wiki).await?;
+        let qids: Vec<u32> = {
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
+            })?;
+            engine_lock.get_wikigraph().art_dense_to_original.clone()
+        };
+
+        let titles_map = Self::get_titles_by_qids(Arc::clone(&state), wiki, &qids).await?;
+        let titled: Vec<(String, u32)> = qids
+            .into_iter()
+            .filter_map(|qid| titles_map.get(&qid).cloned().map(|title| (title, qid)))
+            .collect();
+
+        let index = Arc::new(TitleIndex::build(titled));
+
+        let mut indexes = state.title_indexes.write().map_err(|_| {
+            CoreServiceError::InternalError("Failed to acquire title index lock".to_string())
+        })?;
+        Ok(Arc::clone(
+            indexes.entry(wiki.to_string()).or_insert(index),
+        ))
+    }
+
+    /// Resolves a possibly misspelled or partial `query` against `wiki`'s
+    /// titles, returning up to `limit` `(title, qid, score)` candidates for
+    /// a "did you mean" suggestion instead of a hard [`CoreServiceError::NotFound`].
+    ///
+    /// A candidate qualifies if its normalized title either starts with the
+    /// normalized query or starts with a prefix that is within
+    /// [`max_distance_for_query`]'s edit distance of it. Candidates are ranked by edit distance first (an
+    /// exact prefix counts as distance 0), then by trailing-30-day pageview
+    /// popularity - the same "total views" signal `PageViewService` exposes
+    /// elsewhere in the crate, looked up directly against the engine here to
+    /// avoid a service-to-service detour for a single number.
+    pub async fn resolve_title(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, u32, f32)>, CoreServiceError> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let index = Self::get_or_build_title_index(Arc::clone(&state), wiki).await?;
+        let query_norm = normalize_title(query);
+
+        let typo_tolerance = TypoTolerance::new();
+        let dfa = typo_tolerance.build_dfa(&query_norm);
+
+        let mut candidate_idxs: HashSet<usize> = HashSet::new();
+        index.stream_candidates(Str::new(&query_norm).starts_with(), &mut candidate_idxs);
+        index.stream_candidates(LevenshteinFstAutomaton(&dfa), &mut candidate_idxs);
+
+        let engine = EngineService::get_or_build_engine(Arc::clone(&state), wiki).await?;
+        let end_date = chrono::Local::now().date_naive();
+        let start_date = end_date - chrono::Duration::days(30);
+
+        let mut candidates: Vec<(u8, u64, String, u32)> = Vec::with_capacity(candidate_idxs.len());
+        for idx in candidate_idxs {
+            let (title, qid) = &index.entries[idx];
+            let normalized = normalize_title(title);
+            let distance = if normalized.starts_with(&query_norm) {
+                0
+            } else {
+                match dfa.eval(&normalized) {
+                    Distance::Exact(distance) => distance,
+                    Distance::AtLeast(_) => continue, // stream guarantees a match; defensive only
+                }
+            };
+
+            let total_views: u64 = {
+                let engine_lock = engine.read().map_err(|e| {
+                    CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
+                })?;
+                engine_lock
+                    .get_article_trend(*qid, start_date, end_date)
+                    .iter()
+                    .map(|(_, views)| views)
+                    .sum()
+            };
+
+            candidates.push((distance, total_views, title.clone(), *qid));
+        }
+
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        candidates.truncate(limit);
+
+        Ok(candidates
+            .into_iter()
+            .map(|(distance, total_views, title, qid)| {
+                // Diagnostic only, like `SearchMatch::score` in
+                // `category_searcher` - not the value `sort_by` above used.
+                let score = 1.0 / (1.0 + distance as f32) + (total_views as f32 + 1.0).ln() * 0.01;
+                (title, qid, score)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_title_unifies_case_and_underscores() {
+        assert_eq!(normalize_title("Foo_Bar"), "foo bar");
+        assert_eq!(normalize_title("foo bar"), "foo bar");
+    }
+
+    #[test]
+    fn max_distance_for_query_grows_with_length() {
+        assert_eq!(max_distance_for_query("abcd"), 0);
+        assert_eq!(max_distance_for_query("abcde"), 1);
+        assert_eq!(max_distance_for_query("abcdefgh"), 1);
+        assert_eq!(max_distance_for_query("abcdefghi"), 2);
+    }
+
+    fn build_index() -> TitleIndex {
+        TitleIndex::build(vec![
+            ("Albert Einstein".to_string(), 1),
+            ("Alberta".to_string(), 2),
+            ("Isaac Newton".to_string(), 3),
+        ])
+    }
+
+    /// Mirrors the candidate-gathering half of `resolve_title` (prefix +
+    /// typo-tolerant FST streams) without needing an `AppState`/engine, so
+    /// the FST+Levenshtein index this file introduces has direct coverage.
+    fn candidate_titles(index: &TitleIndex, query: &str) -> HashSet<String> {
+        let query_norm = normalize_title(query);
+        let typo_tolerance = TypoTolerance::new();
+        let dfa = typo_tolerance.build_dfa(&query_norm);
+
+        let mut idxs: HashSet<usize> = HashSet::new();
+        index.stream_candidates(Str::new(&query_norm).starts_with(), &mut idxs);
+        index.stream_candidates(LevenshteinFstAutomaton(&dfa), &mut idxs);
+
+        idxs.into_iter()
+            .map(|idx| index.entries[idx].0.clone())
+            .collect()
+    }
+
+    #[test]
+    fn prefix_query_matches_all_shared_prefix_titles() {
+        let index = build_index();
+        let titles = candidate_titles(&index, "albert");
+        assert!(titles.contains("Albert Einstein"));
+        assert!(titles.contains("Alberta"));
+        assert!(!titles.contains("Isaac Newton"));
+    }
+
+    #[test]
+    fn typo_tolerant_query_matches_within_edit_distance() {
+        let index = build_index();
+        // One transposed letter, a single edit with transpositions enabled;
+        // at 12 characters `max_distance_for_query` allows up to two.
+        let titles = candidate_titles(&index, "isaac newtno");
+        assert!(titles.contains("Isaac Newton"));
+    }
 }
@@ -0,0 +1,224 @@
+use super::{CoreServiceError, EngineService, QidService};
+use crate::models::AppState;
+use std::collections::HashMap;
+use std::sync::Arc;
+use topictrend_taxonomy::sentence_embedder::SentenceEmbedder;
+
+/// Titles embedded per `encode_batch` call while building a semantic index,
+/// to bound request size against the embedding server.
+const INDEX_BATCH_SIZE: usize = 256;
+
+/// The `k` constant in Reciprocal Rank Fusion: `score += 1 / (k + rank)`.
+/// 60 is the standard choice from the original RRF paper.
+const RRF_K: f32 = 60.0;
+
+/// Candidates over-fetched from each ranked list before fusing in
+/// [`SemanticSearchService::hybrid_search`], so RRF has enough of a tail to
+/// draw from beyond `top_k`.
+const HYBRID_OVERFETCH: usize = 5;
+
+/// Which half of the wikigraph a [`SemanticIndex`] covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SemanticTarget {
+    Category,
+    Article,
+}
+
+impl SemanticTarget {
+    /// MediaWiki's page namespace for this target (14 = Category, 0 = main).
+    fn namespace(self) -> i8 {
+        match self {
+            SemanticTarget::Category => 14,
+            SemanticTarget::Article => 0,
+        }
+    }
+}
+
+/// A per-wiki, per-target in-memory vector index: parallel `qids`/`vectors`
+/// (each vector L2-normalized by the embedding server), so ranking a query
+/// against it reduces to a dot-product scan rather than a full ONNX call.
+pub struct SemanticIndex {
+    qids: Vec<u32>,
+    vectors: Vec<Vec<f32>>,
+}
+
+impl SemanticIndex {
+    /// Ranks every entry by cosine similarity to `query_vector` (assumed
+    /// unit-normalized) and returns the top `top_k` `(qid, score)` pairs,
+    /// highest first.
+    fn top_k(&self, query_vector: &[f32], top_k: usize) -> Vec<(u32, f32)> {
+        let mut scored: Vec<(u32, f32)> = self
+            .qids
+            .iter()
+            .zip(self.vectors.iter())
+            .map(|(&qid, vector)| (qid, dot(query_vector, vector)))
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+/// Dot product of two equal-length, unit-normalized vectors is their
+/// cosine similarity.
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+pub struct SemanticSearchService;
+
+impl SemanticSearchService {
+    /// Returns `wiki`'s cached vector index for `target`, building it
+    /// lazily (like [`EngineService::get_or_build_engine`]) on first
+    /// request: every qid in the wikigraph is resolved to a title via
+    /// [`QidService`], embedded in [`INDEX_BATCH_SIZE`]-sized batches, and
+    /// cached so later requests reuse it instead of re-embedding.
+    pub async fn get_or_build_index(
+        state: Arc<AppState>,
+        wiki: &str,
+        target: SemanticTarget,
+    ) -> Result<Arc<SemanticIndex>, CoreServiceError> {
+        let key = (wiki.to_string(), target);
+
+        {
+            let indexes = state.semantic_indexes.read().map_err(|_| {
+                CoreServiceError::InternalError(
+                    "Failed to acquire semantic index lock".to_string(),
+                )
+            })?;
+            if let Some(index) = indexes.get(&key) {
+                return Ok(Arc::clone(index));
+            }
+        }
+
+        let index = Arc::new(Self::build_index(Arc::clone(&state), wiki, target).await?);
+
+        let mut indexes = state.semantic_indexes.write().map_err(|_| {
+            CoreServiceError::InternalError("Failed to acquire semantic index lock".to_string())
+        })?;
+        Ok(Arc::clone(indexes.entry(key).or_insert(index)))
+    }
+
+    async fn build_index(
+        state: Arc<AppState>,
+        wiki: &str,
+        target: SemanticTarget,
+    ) -> Result<SemanticIndex, CoreServiceError> {
+        let engine = EngineService::get_or_build_engine(Arc::clone(&state), wiki).await?;
+        let qids: Vec<u32> = {
+            let engine_lock = engine.read().map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to acquire read lock: {}", e))
+            })?;
+            let wikigraph = engine_lock.get_wikigraph();
+            match target {
+                SemanticTarget::Category => wikigraph.cat_dense_to_original.clone(),
+                SemanticTarget::Article => wikigraph.art_dense_to_original.clone(),
+            }
+        };
+
+        let titles_map = QidService::get_titles_by_qids(Arc::clone(&state), wiki, &qids).await?;
+        let titled: Vec<(u32, String)> = qids
+            .into_iter()
+            .filter_map(|qid| titles_map.get(&qid).cloned().map(|title| (qid, title)))
+            .collect();
+
+        let mut embedder = SentenceEmbedder::new().await.map_err(|e| {
+            CoreServiceError::InternalError(format!(
+                "Failed to connect to embedding server: {}",
+                e
+            ))
+        })?;
+
+        let mut result_qids = Vec::with_capacity(titled.len());
+        let mut result_vectors = Vec::with_capacity(titled.len());
+
+        for chunk in titled.chunks(INDEX_BATCH_SIZE) {
+            let titles: Vec<&str> = chunk.iter().map(|(_, title)| title.as_str()).collect();
+            let vectors = embedder.encode_batch(&titles).await.map_err(|e| {
+                CoreServiceError::InternalError(format!("Failed to embed titles: {}", e))
+            })?;
+
+            for ((qid, _), vector) in chunk.iter().zip(vectors.into_iter()) {
+                result_qids.push(*qid);
+                result_vectors.push(vector);
+            }
+        }
+
+        Ok(SemanticIndex {
+            qids: result_qids,
+            vectors: result_vectors,
+        })
+    }
+
+    /// Embeds `query` and ranks `wiki`'s `target` index against it,
+    /// returning the top `top_k` `(qid, score)` pairs.
+    pub async fn search(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        target: SemanticTarget,
+        top_k: usize,
+    ) -> Result<Vec<(u32, f32)>, CoreServiceError> {
+        let index = Self::get_or_build_index(Arc::clone(&state), wiki, target).await?;
+
+        let mut embedder = SentenceEmbedder::new().await.map_err(|e| {
+            CoreServiceError::InternalError(format!(
+                "Failed to connect to embedding server: {}",
+                e
+            ))
+        })?;
+        let query_vector = embedder.encode(query).await.map_err(|e| {
+            CoreServiceError::InternalError(format!("Failed to embed query: {}", e))
+        })?;
+
+        Ok(index.top_k(&query_vector, top_k))
+    }
+
+    /// Like [`search`](Self::search), but when `hybrid` is set fuses the
+    /// embedding ranking with an exact/prefix keyword match on `query` via
+    /// Reciprocal Rank Fusion: each list contributes `1 / (RRF_K + rank)`
+    /// per qid (1-based rank; a qid absent from a list contributes nothing
+    /// from it), then results are sorted by the summed score. This gives
+    /// robust results when a query has both a literal lexical match and
+    /// semantically related neighbors, without calibrating the two
+    /// incomparable score scales against each other.
+    pub async fn hybrid_search(
+        state: Arc<AppState>,
+        wiki: &str,
+        query: &str,
+        target: SemanticTarget,
+        top_k: usize,
+        hybrid: bool,
+    ) -> Result<Vec<(u32, f32)>, CoreServiceError> {
+        let overfetch = top_k * HYBRID_OVERFETCH;
+        let semantic_hits =
+            Self::search(Arc::clone(&state), wiki, query, target, overfetch).await?;
+
+        if !hybrid {
+            return Ok(semantic_hits.into_iter().take(top_k).collect());
+        }
+
+        let keyword_hits = QidService::search_titles_by_prefix(
+            Arc::clone(&state),
+            wiki,
+            query,
+            target.namespace(),
+            overfetch,
+        )
+        .await?;
+
+        let mut fused: HashMap<u32, f32> = HashMap::new();
+        for (rank, (qid, _)) in semantic_hits.iter().enumerate() {
+            *fused.entry(*qid).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f32);
+        }
+        for (rank, (_, qid)) in keyword_hits.iter().enumerate() {
+            *fused.entry(*qid).or_insert(0.0) += 1.0 / (RRF_K + (rank + 1) as f32);
+        }
+
+        let mut fused: Vec<(u32, f32)> = fused.into_iter().collect();
+        fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        fused.truncate(top_k);
+        Ok(fused)
+    }
+}